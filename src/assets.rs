@@ -0,0 +1,207 @@
+//Everything the game needs from the page is listed in an asset manifest. The page fetches the files and hands them over
+//one by one with FoxGame::provide_asset, the game starts once the assets of the startup section and the first level are there.
+//The assets of other levels can be handed over later, FoxGame::change_level waits for them and then applies them with the level.
+//
+//The manifest is a text file with one entry per line, everything after a # is a comment:
+//  <kind> <name> <file>    asset with a unique name, the file is where the page finds it
+//  level <level key>       the entries below are only needed for that level, e.g. level endless_0
+//
+//Kinds are tile_map, tile_atlas, sprite_tile_map, sprite_atlas, config and sound. The startup section needs a tile_map and a sprite_tile_map,
+//without a tile_atlas or sprite_atlas the one of the bundled tile map or sprite tile map is used. Assets of a level replace those of the startup section
+//while the level is played. Sounds are played by the page, the game only keeps track of whether they arrived.
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum AssetKind
+{
+    TileMap,
+    TileAtlas,
+    SpriteTileMap,
+    SpriteAtlas,
+    Config,
+    Sound,
+}
+
+impl AssetKind
+{
+    pub fn from_name(name: &str) -> Result<AssetKind, String>
+    {
+        match name
+        {
+            "tile_map" => Ok(AssetKind::TileMap),
+            "tile_atlas" => Ok(AssetKind::TileAtlas),
+            "sprite_tile_map" => Ok(AssetKind::SpriteTileMap),
+            "sprite_atlas" => Ok(AssetKind::SpriteAtlas),
+            "config" => Ok(AssetKind::Config),
+            "sound" => Ok(AssetKind::Sound),
+            _ => Err(format!("Asset kind {} not found", name)),
+        }
+    }
+}
+
+pub struct AssetEntry
+{
+    pub kind: AssetKind,
+    pub name: String,
+    pub file: String,
+    //None for assets of the startup section
+    pub level_key: Option<String>,
+}
+
+pub struct AssetManifest
+{
+    entries: std::vec::Vec<AssetEntry>,
+}
+
+impl AssetManifest
+{
+    pub fn parse(text: &str) -> Result<AssetManifest, String>
+    {
+        let mut entries: std::vec::Vec<AssetEntry> = std::vec::Vec::new();
+        let mut level_key: Option<String> = None;
+
+        for (line_index, line) in text.lines().enumerate()
+        {
+            let line = match line.find('#')
+            {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+
+            let words: std::vec::Vec<&str> = line.split_whitespace().collect();
+            let error = |msg: &str| format!("Line {}: {}", line_index + 1, msg);
+
+            match words.len()
+            {
+                0 => continue,
+                2 if words[0] == "level" => level_key = Some(words[1].to_string()),
+                3 =>
+                {
+                    let kind = AssetKind::from_name(words[0]).map_err(|x| error(&x))?;
+                    if entries.iter().any(|x| x.name == words[1])
+                    {
+                        return Err(error(&format!("Asset {} is defined twice", words[1])));
+                    }
+                    entries.push(AssetEntry { kind, name: words[1].to_string(), file: words[2].to_string(), level_key: level_key.clone() });
+                },
+                _ => return Err(error(&format!("Invalid entry {}", line.trim()))),
+            }
+        }
+
+        for kind in [AssetKind::TileMap, AssetKind::SpriteTileMap].iter()
+        {
+            if !entries.iter().any(|x| x.kind == *kind && x.level_key.is_none())
+            {
+                return Err("The startup section of the manifest needs a tile_map and a sprite_tile_map".to_string());
+            }
+        }
+
+        Ok(AssetManifest { entries })
+    }
+
+    //Assets of the startup section and the ones of the level
+    pub fn get_entries_for(&self, level_key: &str) -> std::vec::Vec<&AssetEntry>
+    {
+        self.entries.iter().filter(|x| match &x.level_key { Some(key) => key == level_key, None => true }).collect()
+    }
+
+    pub fn get_entry(&self, name: &str) -> Option<&AssetEntry>
+    {
+        self.entries.iter().find(|x| x.name == name)
+    }
+}
+
+//The assets of a level, decoded. Those the level has no own entry for are taken from the startup section.
+pub struct LevelAssets
+{
+    pub tile_map: image::RgbaImage,
+    //None for the atlas of the bundled tile map
+    pub tile_atlas_descriptor: Option<String>,
+    pub sprite_tile_map: image::RgbaImage,
+    //None for the atlas of the bundled sprite tile map
+    pub sprite_atlas_descriptor: Option<String>,
+    //Settings that replace those of the difficulty
+    pub config_text: Option<String>,
+}
+
+pub struct AssetStore
+{
+    manifest: AssetManifest,
+    data: std::collections::HashMap<String, std::vec::Vec<u8>>,
+}
+
+impl AssetStore
+{
+    pub fn new(manifest: AssetManifest) -> AssetStore
+    {
+        AssetStore { manifest, data: std::collections::HashMap::new() }
+    }
+
+    pub fn provide(&mut self, name: &str, bytes: std::vec::Vec<u8>) -> Result<(), String>
+    {
+        self.manifest.get_entry(name).ok_or(format!("Asset {} is not in the manifest", name))?;
+        self.data.insert(name.to_string(), bytes);
+        Ok(())
+    }
+
+    pub fn get_entry(&self, name: &str) -> Option<&AssetEntry>
+    {
+        self.manifest.get_entry(name)
+    }
+
+    pub fn get_missing(&self, level_key: &str) -> std::vec::Vec<&AssetEntry>
+    {
+        self.manifest.get_entries_for(level_key).into_iter().filter(|x| !self.data.contains_key(&x.name)).collect()
+    }
+
+    //Share of the assets needed for the level that arrived, from 0 to 1
+    pub fn get_progress(&self, level_key: &str) -> f32
+    {
+        let needed = self.manifest.get_entries_for(level_key).len();
+        if needed == 0
+        {
+            return 1.0;
+        }
+        (needed - self.get_missing(level_key).len()) as f32 / needed as f32
+    }
+
+    pub fn is_complete(&self, level_key: &str) -> bool
+    {
+        self.get_missing(level_key).is_empty()
+    }
+
+    //The asset of the level if it has one of that kind, otherwise the one of the startup section
+    pub fn get(&self, kind: AssetKind, level_key: &str) -> Option<&[u8]>
+    {
+        let entries = self.manifest.get_entries_for(level_key);
+        let entry = entries.iter().find(|x| x.kind == kind && x.level_key.is_some())
+            .or_else(|| entries.iter().find(|x| x.kind == kind))?;
+        self.data.get(&entry.name).map(|x| x.as_slice())
+    }
+
+    pub fn get_level_assets(&self, level_key: &str) -> Result<LevelAssets, String>
+    {
+        let missing = |name: &str| format!("Asset {} of {} is missing", name, level_key);
+        let text = |kind: AssetKind, name: &str| self.get(kind, level_key).map(|bytes| load_text(bytes, name)).transpose();
+
+        Ok(LevelAssets {
+            tile_map: load_image(self.get(AssetKind::TileMap, level_key).ok_or_else(|| missing("tile_map"))?, "tile map")?,
+            tile_atlas_descriptor: text(AssetKind::TileAtlas, "tile atlas")?,
+            sprite_tile_map: load_image(self.get(AssetKind::SpriteTileMap, level_key).ok_or_else(|| missing("sprite_tile_map"))?, "sprite tile map")?,
+            sprite_atlas_descriptor: text(AssetKind::SpriteAtlas, "sprite atlas")?,
+            config_text: text(AssetKind::Config, "config")?,
+        })
+    }
+}
+
+//Decodes images handed over by the page. The format (bmp, png, ...) is detected from the data itself.
+pub fn load_image(data: &[u8], name: &str) -> Result<image::RgbaImage, String>
+{
+    let format = image::guess_format(data).map_err(|x| format!("Unknown image format of {}: {}", name, x))?;
+    let image = image::load_from_memory_with_format(data, format).map_err(|x| format!("Failed to load {}: {}", name, x))?;
+    Ok(image.to_rgba8())
+}
+
+pub fn load_text(data: &[u8], name: &str) -> Result<String, String>
+{
+    String::from_utf8(data.to_vec()).map_err(|_| format!("{} is not valid text", name))
+}
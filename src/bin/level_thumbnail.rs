@@ -0,0 +1,59 @@
+//Renders the start of a level on the CPU, e.g. for the level thumbnails of the website.
+//
+//Usage: cargo run --bin level_thumbnail -- <game mode> <level> <output image> [width height]
+//e.g.   cargo run --bin level_thumbnail -- 0 0 campaign_0.png 320 240
+//
+//The game mode is 0 for campaign and 1 for endless, the format of the output image is taken from its file extension.
+//The bundled tile maps, sprite atlas and default config are used.
+
+use for_fox_sake::model::Model;
+use for_fox_sake::model::game_mode::GameMode;
+use for_fox_sake::model::game_config::{GameConfig, Difficulty};
+use for_fox_sake::sprite_atlas::SpriteAtlas;
+use for_fox_sake::view::software_renderer::SoftwareRenderer;
+
+fn main()
+{
+    let args: std::vec::Vec<String> = std::env::args().collect();
+    if args.len() != 4 && args.len() != 6
+    {
+        eprintln!("Usage: {} <game mode> <level> <output image> [width height]", args[0]);
+        std::process::exit(1);
+    }
+
+    match render(&args[1..])
+    {
+        Ok(()) => println!("Rendered {}", args[3]),
+        Err(err_msg) =>
+        {
+            eprintln!("{}", err_msg);
+            std::process::exit(1);
+        },
+    }
+}
+
+fn render(args: &[String]) -> Result<(), String>
+{
+    let parse_number = |text: &str| text.parse::<u32>().map_err(|_| format!("{} is not a number", text));
+    let mode = GameMode::from(parse_number(&args[0])? as u8)?;
+    let level_code = parse_number(&args[1])? as u8;
+    let (width, height) = match args.len()
+    {
+        5 => (parse_number(&args[3])?, parse_number(&args[4])?),
+        _ => (512, 512),
+    };
+
+    let load_image = |path: &str| image::open(path).map(|x| x.to_rgba8()).map_err(|x| format!("Failed to load {}: {}", path, x));
+    let tile_map = load_image("resources/testTileMap.bmp")?;
+    let tile_atlas = SpriteAtlas::parse(include_str!("../../resources/testTileMap.atlas"))?;
+    let sprite_tile_map = load_image("resources/spriteTileMap.bmp")?;
+    let sprite_atlas = SpriteAtlas::parse(include_str!("../../resources/spriteTileMap.atlas"))?;
+
+    let mut model = Model::new(mode, GameConfig::from_difficulty(Difficulty::from(1)?), &sprite_atlas)?;
+    let level = model.load_level(level_code)?;
+
+    let renderer = SoftwareRenderer::new(tile_map, &tile_atlas, sprite_tile_map, sprite_atlas, width, height)?;
+    let thumbnail = renderer.render_with_lights(&level, &model.to_sprites_view_model()?, &model.to_particles_view_model(), model.to_lights_view_model().as_ref(), &model.to_post_process_view_model(), &model.to_camera_view_model())?;
+
+    thumbnail.save(&args[2]).map_err(|x| format!("Failed to write {}: {}", args[2], x))
+}
@@ -0,0 +1,207 @@
+//Packs all images of a directory into one atlas image and writes the matching sprite atlas descriptor (see sprite_atlas.rs).
+//
+//Usage: cargo run --bin pack_atlas -- <image directory> <atlas image> <atlas descriptor>
+//e.g.   cargo run --bin pack_atlas -- resources/Raws resources/rawsTileMap.bmp resources/rawsTileMap.atlas
+//
+//The frames are named after the files in snake case, so QuoteFoxQuote.png becomes quote_fox_quote.
+//If all images have the same size they are put into a grid with cell entries, counted row by row in the order of the file names,
+//so the atlas also works as tile map where the tile index is the position of the cell. Otherwise they are packed in rows with frame entries.
+//The format of the atlas image is taken from its file extension.
+
+use for_fox_sake::sprite_atlas::SpriteAtlas;
+
+use image::GenericImage;
+
+struct Sprite
+{
+    name: String,
+    image: image::RgbaImage,
+}
+
+struct Placement
+{
+    name: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+fn main()
+{
+    let args: std::vec::Vec<String> = std::env::args().collect();
+    if args.len() != 4
+    {
+        eprintln!("Usage: {} <image directory> <atlas image> <atlas descriptor>", args[0]);
+        std::process::exit(1);
+    }
+
+    match pack(&args[1], &args[2], &args[3])
+    {
+        Ok(count) => println!("Packed {} images into {} and {}", count, args[2], args[3]),
+        Err(err_msg) =>
+        {
+            eprintln!("{}", err_msg);
+            std::process::exit(1);
+        },
+    }
+}
+
+fn pack(input_directory: &str, image_path: &str, descriptor_path: &str) -> Result<usize, String>
+{
+    let sprites = load_sprites(input_directory)?;
+    if sprites.is_empty()
+    {
+        return Err(format!("No images found in {}", input_directory));
+    }
+
+    let first_size = sprites[0].image.dimensions();
+    let (atlas_width, atlas_height, placements, grid) = if sprites.iter().all(|x| x.image.dimensions() == first_size)
+    {
+        let (width, height, placements, columns, rows) = place_in_grid(&sprites);
+        (width, height, placements, Some((columns, rows)))
+    }
+    else
+    {
+        let (width, height, placements) = place_in_rows(&sprites);
+        (width, height, placements, None)
+    };
+
+    let mut atlas = image::RgbaImage::new(atlas_width, atlas_height);
+    for (sprite, placement) in sprites.iter().zip(placements.iter())
+    {
+        atlas.copy_from(&sprite.image, placement.x, placement.y).map_err(|x| format!("Failed to copy {}: {}", sprite.name, x))?;
+    }
+
+    let mut descriptor = String::new();
+    descriptor.push_str(&format!("# Generated by pack_atlas from {}\n", input_directory));
+    descriptor.push_str(&format!("size {} {}\n", atlas_width, atlas_height));
+    match grid
+    {
+        Some((columns, rows)) =>
+        {
+            descriptor.push_str(&format!("grid {} {}\n", columns, rows));
+            for placement in placements.iter()
+            {
+                descriptor.push_str(&format!("cell {} {} {}\n", placement.name, placement.x / placement.width, placement.y / placement.height));
+            }
+        },
+        None =>
+        {
+            for placement in placements.iter()
+            {
+                descriptor.push_str(&format!("frame {} {} {} {} {}\n", placement.name, placement.x, placement.y, placement.width, placement.height));
+            }
+        },
+    }
+
+    //Make sure the game is able to load what was written
+    SpriteAtlas::parse(&descriptor)?;
+
+    atlas.save(image_path).map_err(|x| format!("Failed to write {}: {}", image_path, x))?;
+    std::fs::write(descriptor_path, descriptor).map_err(|x| format!("Failed to write {}: {}", descriptor_path, x))?;
+
+    Ok(sprites.len())
+}
+
+fn load_sprites(input_directory: &str) -> Result<std::vec::Vec<Sprite>, String>
+{
+    let entries = std::fs::read_dir(input_directory).map_err(|x| format!("Failed to read {}: {}", input_directory, x))?;
+
+    let mut paths: std::vec::Vec<std::path::PathBuf> = entries
+        .filter_map(|x| x.ok())
+        .map(|x| x.path())
+        .filter(|x| x.is_file() && image::ImageFormat::from_path(x).is_ok())
+        .collect();
+    paths.sort();
+
+    let mut sprites = std::vec::Vec::new();
+    for path in paths.iter()
+    {
+        let stem = path.file_stem().and_then(|x| x.to_str()).ok_or(format!("Invalid file name {}", path.display()))?;
+        let image = image::open(path).map_err(|x| format!("Failed to load {}: {}", path.display(), x))?.to_rgba8();
+        sprites.push(Sprite { name: to_snake_case(stem), image });
+    }
+
+    Ok(sprites)
+}
+
+//Cells of a grid that is as square as possible
+fn place_in_grid(sprites: &[Sprite]) -> (u32, u32, std::vec::Vec<Placement>, u32, u32)
+{
+    let (cell_width, cell_height) = sprites[0].image.dimensions();
+    let count = sprites.len() as u32;
+    let columns = (count as f32).sqrt().ceil() as u32;
+    let rows = count.div_ceil(columns);
+
+    let placements = sprites.iter().enumerate().map(|(i, sprite)| Placement {
+        name: sprite.name.clone(),
+        x: (i as u32 % columns) * cell_width,
+        y: (i as u32 / columns) * cell_height,
+        width: cell_width,
+        height: cell_height,
+    }).collect();
+
+    (columns * cell_width, rows * cell_height, placements, columns, rows)
+}
+
+//Fills rows from left to right, the tallest images first so the rows waste little space.
+//The placements are returned in the same order as the sprites.
+fn place_in_rows(sprites: &[Sprite]) -> (u32, u32, std::vec::Vec<Placement>)
+{
+    let total_area: u32 = sprites.iter().map(|x| x.image.width() * x.image.height()).sum();
+    let widest = sprites.iter().map(|x| x.image.width()).max().unwrap_or(1);
+    let atlas_width = std::cmp::max(widest, ((total_area as f32).sqrt().ceil() as u32).next_power_of_two());
+
+    let mut order: std::vec::Vec<usize> = (0..sprites.len()).collect();
+    order.sort_by_key(|x| std::cmp::Reverse(sprites[*x].image.height()));
+
+    let mut placements: std::vec::Vec<Option<Placement>> = sprites.iter().map(|_| None).collect();
+    let mut x = 0;
+    let mut y = 0;
+    let mut row_height = 0;
+    for index in order
+    {
+        let (width, height) = sprites[index].image.dimensions();
+        if x + width > atlas_width
+        {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+
+        placements[index] = Some(Placement { name: sprites[index].name.clone(), x, y, width, height });
+        x += width;
+        row_height = std::cmp::max(row_height, height);
+    }
+
+    (atlas_width, y + row_height, placements.into_iter().map(|x| x.expect("Every sprite is placed")).collect())
+}
+
+fn to_snake_case(name: &str) -> String
+{
+    let mut snake_case = String::new();
+    for c in name.chars()
+    {
+        if c.is_uppercase()
+        {
+            if !snake_case.is_empty() && !snake_case.ends_with('_')
+            {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        }
+        else if c == ' ' || c == '-' || c == '_'
+        {
+            if !snake_case.ends_with('_')
+            {
+                snake_case.push('_');
+            }
+        }
+        else
+        {
+            snake_case.push(c);
+        }
+    }
+    snake_case
+}
@@ -1,9 +1,21 @@
 use crate::model::{Model};
+use crate::model::game_config::{GameConfig, Difficulty};
+use crate::model::model_event::ModelEvent;
 use crate::view::{View};
-mod input;
+use crate::view::webgl_backend::WebGlBackend;
+use crate::view::scale_mode::ScaleMode;
+use crate::view::texture_filter::TextureFilter;
+use crate::view::transition::{Transition, TransitionKind};
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::sprite_atlas::SpriteAtlas;
+pub mod input;
 use input::Input;
 pub mod read_only_input;
 use read_only_input::ReadOnlyInput;
+pub mod personal_best_storage;
+use personal_best_storage::PersonalBestStorage;
+pub mod game_settings;
+use game_settings::GameSettings;
 
 use image;
 
@@ -13,49 +25,260 @@ pub struct ForFoxSake
 {
     model: Model,
     view: View,
-    context: WebGl2RenderingContext,
     input: Input,
+    personal_best_storage: Box<dyn PersonalBestStorage>,
+    tick_accumulator: f32,
+    run_submitted: bool,
+    //Model events collected since the page last asked for them, at most MAX_PENDING_EVENTS
+    pending_events: std::vec::Vec<ModelEvent>,
 }
 
 impl ForFoxSake
 {
-    pub fn new(context: WebGl2RenderingContext, tile_map: image::RgbaImage, sprite_tile_map: image::RgbaImage, width: i32, height: i32) -> Result<ForFoxSake, String>
-    {
-        let mut model = Model::new()?;
-        let view = View::new(&context, tile_map, sprite_tile_map, width, height)?;
+    //Upper bound of simulation ticks per update, so a long frame (e.g. a tab in the background) does not stall the game
+    const MAX_TICKS_PER_UPDATE: u32 = 10;
+    //A page that never asks for the events only keeps the latest ones
+    const MAX_PENDING_EVENTS: usize = 64;
+    const DAMAGE_FLASH_DURATION: f32 = 0.3;
+    const DAMAGE_ABERRATION_DURATION: f32 = 0.4;
+    const DEATH_DESATURATION_DURATION: f32 = 1.5;
+    const TRANSITION_DURATION: f32 = 1.0;
 
-        let level = model.load_level(0)?;
-        view.update_map(&context, level)?;
+    pub fn new(context: WebGl2RenderingContext, tile_map: image::RgbaImage, sprite_tile_map: image::RgbaImage, width: i32, height: i32, settings: GameSettings) -> Result<ForFoxSake, String>
+    {
+        let model = Model::new(settings.game_mode, settings.config, &settings.sprite_atlas)?;
+        let view = View::new(Box::new(WebGlBackend::new(context)), tile_map, settings.tile_atlas, sprite_tile_map, settings.sprite_atlas, width, height)?;
 
         let input = Input::new();
 
-        Ok(ForFoxSake {
-            model: model,
-            view: view,
-            context,
+        let mut game = ForFoxSake {
+            model,
+            view,
             input,
-        })
+            personal_best_storage: settings.personal_best_storage,
+            tick_accumulator: 0.0,
+            run_submitted: false,
+            pending_events: std::vec::Vec::new(),
+        };
+
+        game.load_level(0)?;
+
+        Ok(game)
+    }
+
+    fn load_level(&mut self, level_code: u8) -> Result<(), String>
+    {
+        let level = self.model.load_level(level_code)?;
+        self.view.update_map(level)?;
+
+        let personal_best = self.personal_best_storage.load(&self.model.get_level_key());
+        self.model.set_ghost(personal_best.map(|x| x.ghost));
+        self.run_submitted = false;
+
+        Ok(())
+    }
+
+    //Loads the level behind a transition from the last frame of the old one. The model waits until the transition has finished,
+    //so nothing happens to the fox while it can not be seen.
+    pub fn change_level(&mut self, level_code: u8, kind: TransitionKind) -> Result<(), String>
+    {
+        let old_center = self.get_fox_screen_position();
+        self.view.snapshot_scene()?;
+        self.load_level(level_code)?;
+        self.tick_accumulator = 0.0;
+
+        let transition = Transition::new(kind, ForFoxSake::TRANSITION_DURATION, old_center, self.get_fox_screen_position());
+        self.view.start_transition(transition)
+    }
+
+    //Like change_level, but the level is the start of a new run with all lives and no score
+    pub fn start_run(&mut self, level_code: u8, kind: TransitionKind) -> Result<(), String>
+    {
+        self.model.start_run();
+        self.change_level(level_code, kind)
+    }
+
+    //In uvs of the screen, from 0 in the bottom left to 1 in the top right
+    fn get_fox_screen_position(&self) -> cgmath::Vector2<f32>
+    {
+        let clip = self.model.to_camera_view_model().to_screen(self.model.get_player_position());
+        (clip + cgmath::Vector2 { x: 1.0, y: 1.0 }) / 2.0
+    }
+
+    fn submit_finished_run(&mut self)
+    {
+        if self.run_submitted || !self.model.is_level_completed()
+        {
+            return;
+        }
+
+        let level_key = self.model.get_level_key();
+        let finished_run = self.model.get_finished_run();
+        let is_new_personal_best = match self.personal_best_storage.load(&level_key)
+        {
+            Some(personal_best) => finished_run.ticks < personal_best.ticks,
+            None => true,
+        };
+
+        if is_new_personal_best
+        {
+            self.personal_best_storage.save(&level_key, finished_run);
+        }
+        self.run_submitted = true;
+    }
+
+    fn handle_model_events(&mut self)
+    {
+        for event in self.model.take_events()
+        {
+            match event
+            {
+                ModelEvent::FoxDamaged { .. } =>
+                {
+                    self.view.flash_screen(ForFoxSake::DAMAGE_FLASH_DURATION);
+                    self.start_default_effect(PostProcessEffects::CHROMATIC_ABERRATION, ForFoxSake::DAMAGE_ABERRATION_DURATION);
+                },
+                ModelEvent::FoxDied { .. } =>
+                {
+                    self.view.flash_screen(ForFoxSake::DAMAGE_FLASH_DURATION);
+                    self.start_default_effect(PostProcessEffects::DESATURATE, ForFoxSake::DEATH_DESATURATION_DURATION);
+                },
+                ModelEvent::GameOver | ModelEvent::AnimationEvent { .. } => (),
+            }
+            if self.pending_events.len() == ForFoxSake::MAX_PENDING_EVENTS
+            {
+                self.pending_events.remove(0);
+            }
+            self.pending_events.push(event);
+        }
+    }
+
+    fn start_default_effect(&mut self, name: PostProcessEffects, duration: f32)
+    {
+        self.view.start_effect(name, duration, PostProcessParameters::default_for(name));
+    }
+
+    //With the look the effect has for events, see PostProcessParameters::default_for
+    pub fn start_post_process_effect(&mut self, name: PostProcessEffects, duration: f32)
+    {
+        self.start_default_effect(name, duration);
     }
 
     pub fn update(&mut self, delta_time: f32)
     {
-        let read_only_input = ReadOnlyInput::new(&self.input);
-        self.model.update(read_only_input, delta_time);
-        match self.view.update(&self.context, self.model.to_sprites_view_model(), self.model.to_particles_view_model(), self.model.to_post_process_view_model())
+        //The model runs on fixed ticks, so the outcome of a run does not depend on the frame rate
+        if !self.view.is_transition_running()
+        {
+            self.tick_accumulator += delta_time;
+        }
+        let mut ticks = 0;
+        let mut model_result = Ok(());
+        while self.tick_accumulator >= Model::TICK_DURATION && ticks < ForFoxSake::MAX_TICKS_PER_UPDATE && model_result.is_ok()
+        {
+            let read_only_input = ReadOnlyInput::new(&self.input);
+            model_result = self.model.update(read_only_input, Model::TICK_DURATION);
+            self.input.finalize();
+            self.tick_accumulator -= Model::TICK_DURATION;
+            ticks += 1;
+        }
+
+        if ticks == ForFoxSake::MAX_TICKS_PER_UPDATE
+        {
+            self.tick_accumulator = 0.0;
+        }
+
+        self.submit_finished_run();
+        self.handle_model_events();
+
+        let lights = self.model.to_lights_view_model();
+        match model_result
+            .and_then(|_| self.model.to_sprites_view_model())
+            .and_then(|sprites| self.view.update(sprites, self.model.to_particles_view_model(), self.model.to_post_process_view_model(), self.model.to_camera_view_model(), delta_time))
+            .and_then(|_| self.view.update_lights(lights))
         {
             Ok(_) => (),
             Err(err_msg) => 
             {
-                web_sys::console::log_1(&format!("{}", err_msg).into());
-                panic!(err_msg);
+                web_sys::console::log_1(&err_msg.to_string().into());
+                panic!("{}", err_msg);
             },
         };
-        self.input.finalize();
     }
 
-    pub fn draw(&self)
+    pub fn draw(&mut self)
+    {
+        self.view.draw()
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty)
+    {
+        self.model.set_config(GameConfig::from_difficulty(difficulty));
+    }
+
+    //Settings missing in the text keep their current value
+    pub fn set_config(&mut self, config_text: &str) -> Result<(), String>
+    {
+        let config = GameConfig::parse(config_text, self.model.get_config().clone())?;
+        self.model.set_config(config);
+        Ok(())
+    }
+
+    pub fn set_tile_map(&mut self, tile_map: image::RgbaImage, tile_atlas: SpriteAtlas) -> Result<(), String>
+    {
+        self.view.set_tile_map(tile_map, tile_atlas)
+    }
+
+    pub fn resize(&mut self, width: i32, height: i32) -> Result<(), String>
+    {
+        self.view.resize(width, height)
+    }
+
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) -> Result<(), String>
+    {
+        self.view.set_scale_mode(scale_mode)
+    }
+
+    pub fn set_render_resolution(&mut self, resolution: Option<(i32, i32)>) -> Result<(), String>
+    {
+        self.view.set_render_resolution(resolution)
+    }
+
+    pub fn set_texture_filter(&mut self, texture_filter: TextureFilter)
+    {
+        self.view.set_texture_filter(texture_filter);
+    }
+
+    pub fn set_post_process_effect_order(&mut self, order: std::vec::Vec<PostProcessEffects>)
+    {
+        self.view.set_post_process_effect_order(order);
+    }
+
+    pub fn set_post_process_effect_enabled(&mut self, effect_type: PostProcessEffects, enabled: bool)
+    {
+        self.view.set_post_process_effect_enabled(effect_type, enabled);
+    }
+
+    pub fn add_screen_shake(&mut self, trauma: f32)
+    {
+        self.model.add_trauma(trauma);
+    }
+
+    pub fn set_screen_shake_enabled(&mut self, enabled: bool)
+    {
+        self.model.set_screen_shake_enabled(enabled);
+    }
+
+    pub fn set_map_tile(&mut self, col: u32, row: u32, tile: i32) -> Result<(), String>
     {
-        self.view.draw(&self.context)
+        self.view.set_map_tile(col, row, tile)
+    }
+
+    pub fn set_sprite_tile_map(&mut self, sprite_tile_map: image::RgbaImage, sprite_atlas: SpriteAtlas) -> Result<(), String>
+    {
+        //The model only switches once the view has its texture, so a failed swap leaves everything as it was
+        Model::check_sprite_atlas(&sprite_atlas)?;
+        self.view.set_sprite_tile_map(sprite_tile_map, sprite_atlas.clone())?;
+        self.model.set_sprite_atlas(sprite_atlas)
     }
 
     pub fn key_down(&mut self, key_code: i32)
@@ -67,4 +290,73 @@ impl ForFoxSake
     {
         self.input.key_up(key_code);
     }
+
+    pub fn get_level_key(&self) -> String
+    {
+        self.model.get_level_key()
+    }
+
+    pub fn get_level_code(&self) -> u8
+    {
+        self.model.get_level_code()
+    }
+
+    pub fn get_survival_time(&self) -> f32
+    {
+        self.model.get_survival_time()
+    }
+
+    pub fn get_score(&self) -> u32
+    {
+        self.model.get_score()
+    }
+
+    pub fn is_level_completed(&self) -> bool
+    {
+        self.model.is_level_completed()
+    }
+
+    pub fn get_rank(&self) -> Option<String>
+    {
+        self.model.get_rank().map(|rank| rank.get_name().to_string())
+    }
+
+    pub fn get_run_time(&self) -> f32
+    {
+        self.model.get_run_ticks() as f32 * Model::TICK_DURATION
+    }
+
+    pub fn get_splits(&self) -> std::vec::Vec<f32>
+    {
+        self.model.get_splits().iter().map(|x| *x as f32 * Model::TICK_DURATION).collect()
+    }
+
+    pub fn get_personal_best_time(&self) -> Option<f32>
+    {
+        self.personal_best_storage.load(&self.model.get_level_key()).map(|x| x.ticks as f32 * Model::TICK_DURATION)
+    }
+
+    pub fn get_personal_best_splits(&self) -> std::vec::Vec<f32>
+    {
+        match self.personal_best_storage.load(&self.model.get_level_key())
+        {
+            Some(personal_best) => personal_best.splits.iter().map(|x| *x as f32 * Model::TICK_DURATION).collect(),
+            None => std::vec::Vec::new(),
+        }
+    }
+
+    pub fn get_hit_points(&self) -> i32
+    {
+        self.model.get_hit_points()
+    }
+
+    pub fn get_lives(&self) -> u32
+    {
+        self.model.get_lives()
+    }
+
+    pub fn take_events(&mut self) -> std::vec::Vec<ModelEvent>
+    {
+        std::mem::take(&mut self.pending_events)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,14 @@
+use crate::model::game_mode::GameMode;
+use crate::model::game_config::GameConfig;
+use crate::sprite_atlas::SpriteAtlas;
+use crate::for_fox_sake::personal_best_storage::PersonalBestStorage;
+
+//Everything besides the context, the textures and the canvas size that a game is started with
+pub struct GameSettings
+{
+    pub game_mode: GameMode,
+    pub config: GameConfig,
+    pub tile_atlas: SpriteAtlas,
+    pub sprite_atlas: SpriteAtlas,
+    pub personal_best_storage: Box<dyn PersonalBestStorage>,
+}
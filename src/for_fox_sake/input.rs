@@ -11,16 +11,16 @@ lazy_static::lazy_static!
 {
     static ref KEY_MAP: std::collections::HashMap<i32, String> = 
     [
-        (38, format!("MoveUp")),
-        (39, format!("MoveRight")),
-        (40, format!("MoveDown")),
-        (37, format!("MoveLeft")),
-        (87, format!("MoveUp")),
-        (68, format!("MoveRight")),
-        (83, format!("MoveDown")),
-        (65, format!("MoveLeft")),
-        (69, format!("Use")),
-        (32, format!("Use")),
+        (38, "MoveUp".to_string()),
+        (39, "MoveRight".to_string()),
+        (40, "MoveDown".to_string()),
+        (37, "MoveLeft".to_string()),
+        (87, "MoveUp".to_string()),
+        (68, "MoveRight".to_string()),
+        (83, "MoveDown".to_string()),
+        (65, "MoveLeft".to_string()),
+        (69, "Use".to_string()),
+        (32, "Use".to_string()),
     ].iter().cloned().collect();
 }
 
@@ -57,9 +57,9 @@ impl Input
         let keys_already_pressed : std::collections::HashMap<i32, bool> = std::collections::HashMap::new();
 
         Input {
-            keys_already_pressed: keys_already_pressed,
-            keys_pressed: keys_pressed,
-            keys_pressed_last_frame: keys_pressed_last_frame,
+            keys_already_pressed,
+            keys_pressed,
+            keys_pressed_last_frame,
         }
     }
 
@@ -67,7 +67,7 @@ impl Input
     {
         for key in self.keys_pressed.clone().keys()
         {
-            *self.keys_pressed_last_frame.get_mut(key).unwrap() = self.keys_pressed.get(key).unwrap().clone();
+            *self.keys_pressed_last_frame.get_mut(key).unwrap() = *self.keys_pressed.get(key).unwrap();
         }
     }
 
@@ -78,10 +78,7 @@ impl Input
 
     pub fn key_down(&mut self, key_code: i32)
     {
-        if !self.keys_already_pressed.contains_key(&key_code)
-        {
-            self.keys_already_pressed.insert(key_code, false);
-        }
+        self.keys_already_pressed.entry(key_code).or_insert(false);
 
 
         if !*self.keys_already_pressed.get(&key_code).unwrap()
@@ -90,7 +87,7 @@ impl Input
             {
                 if let Some(x) = self.keys_pressed.get_mut(input_string)
                 {
-                    *x = *x + 1;
+                    *x += 1;
                 }
             }
             
@@ -100,10 +97,7 @@ impl Input
 
     pub fn key_up(&mut self, key_code: i32)
     {
-        if !self.keys_already_pressed.contains_key(&key_code)
-        {
-            self.keys_already_pressed.insert(key_code, false);
-        }
+        self.keys_already_pressed.entry(key_code).or_insert(false);
 
         if *self.keys_already_pressed.get(&key_code).unwrap()
         {
@@ -111,7 +105,7 @@ impl Input
             {
                 if let Some(x) = self.keys_pressed.get_mut(input_string)
                 {
-                    *x = *x - 1;
+                    *x -= 1;
                 }
             }
 
@@ -129,4 +123,12 @@ impl Input
         *self.keys_pressed_last_frame.get(input_string).unwrap() > 0
     }
 
-}
\ No newline at end of file
+}
+
+impl Default for Input
+{
+    fn default() -> Input
+    {
+        Input::new()
+    }
+}
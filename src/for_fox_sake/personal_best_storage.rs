@@ -0,0 +1,41 @@
+use crate::model::speedrun::PersonalBest;
+
+pub trait PersonalBestStorage
+{
+    fn load(&self, level_key: &str) -> Option<PersonalBest>;
+    fn save(&mut self, level_key: &str, personal_best: PersonalBest);
+}
+
+pub struct InMemoryPersonalBestStorage
+{
+    personal_bests: std::collections::HashMap<String, PersonalBest>,
+}
+
+impl InMemoryPersonalBestStorage
+{
+    pub fn new() -> InMemoryPersonalBestStorage
+    {
+        InMemoryPersonalBestStorage { personal_bests: std::collections::HashMap::new() }
+    }
+}
+
+impl Default for InMemoryPersonalBestStorage
+{
+    fn default() -> InMemoryPersonalBestStorage
+    {
+        InMemoryPersonalBestStorage::new()
+    }
+}
+
+impl PersonalBestStorage for InMemoryPersonalBestStorage
+{
+    fn load(&self, level_key: &str) -> Option<PersonalBest>
+    {
+        self.personal_bests.get(level_key).cloned()
+    }
+
+    fn save(&mut self, level_key: &str, personal_best: PersonalBest)
+    {
+        self.personal_bests.insert(level_key.to_string(), personal_best);
+    }
+}
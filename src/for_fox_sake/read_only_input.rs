@@ -7,25 +7,25 @@ pub struct ReadOnlyInput<'a>
 
 impl<'a> ReadOnlyInput<'a>
 {
-    pub fn new(input: &'a Input) -> ReadOnlyInput
+    pub fn new(input: &'a Input) -> ReadOnlyInput<'a>
     {
         ReadOnlyInput {
-            input: input,
+            input,
         }
     }
 
     pub fn is_input_down(&self, input_string: &str) -> bool
     {
-        self.input.is_input_pressed_this_frame(&format!("{}", input_string)) && !self.input.is_input_pressed_last_frame(&format!("{}", input_string))
+        self.input.is_input_pressed_this_frame(&input_string.to_string()) && !self.input.is_input_pressed_last_frame(&input_string.to_string())
     }
 
     pub fn is_input_pressed(&self, input_string: &str) -> bool
     {
-        self.input.is_input_pressed_this_frame(&format!("{}", input_string)) && self.input.is_input_pressed_last_frame(&format!("{}", input_string))
+        self.input.is_input_pressed_this_frame(&input_string.to_string()) && self.input.is_input_pressed_last_frame(&input_string.to_string())
     }
 
     pub fn is_input_up(&self, input_string: &str) -> bool
     {
-        !self.input.is_input_pressed_this_frame(&format!("{}", input_string)) && self.input.is_input_pressed_last_frame(&format!("{}", input_string))
+        !self.input.is_input_pressed_this_frame(&input_string.to_string()) && self.input.is_input_pressed_last_frame(&input_string.to_string())
     }
 }
\ No newline at end of file
@@ -1,16 +1,16 @@
 //This file represents the interface between the wasm and the js
 mod utils;
-mod for_fox_sake;
-mod model;
-mod view;
-mod view_models;
+pub mod assets;
+pub mod for_fox_sake;
+pub mod model;
+pub mod view;
+pub mod view_models;
+pub mod sprite_atlas;
 
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext};
 
-use image;
-
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
 #[cfg(feature = "wee_alloc")]
@@ -32,7 +32,24 @@ pub fn greet()
 #[wasm_bindgen]
 pub struct FoxGame
 {
-    game: for_fox_sake::ForFoxSake,
+    //None until the assets needed to start have been provided
+    game: Option<for_fox_sake::ForFoxSake>,
+    assets: assets::AssetStore,
+    context: WebGl2RenderingContext,
+    canvas_width: i32,
+    canvas_height: i32,
+    game_mode: model::game_mode::GameMode,
+    difficulty: model::game_config::Difficulty,
+    screen_shake_enabled: bool,
+    scale_mode: view::scale_mode::ScaleMode,
+    render_resolution: Option<(i32, i32)>,
+    texture_filter: view::texture_filter::TextureFilter,
+    post_process_effect_order: Option<std::vec::Vec<view_models::PostProcessEffects>>,
+    disabled_post_process_effects: std::collections::HashSet<view_models::PostProcessEffects>,
+    //Level that is loaded once all its assets arrived
+    pending_level: Option<(u8, view::transition::TransitionKind)>,
+    //Whether the pending level starts a new run, see start_run
+    pending_new_run: bool,
 }
 
 extern crate web_sys;
@@ -41,48 +58,455 @@ extern crate web_sys;
 #[wasm_bindgen]
 impl FoxGame
 {
-    pub fn new(canvas_id: String, canvas_width: i32, canvas_height: i32, tile_map_raw_data: std::vec::Vec<u8>, sprite_tile_map_raw_data: std::vec::Vec<u8>) -> Result<FoxGame, JsValue>
+    //The manifest lists the assets to hand over with provide_asset, see resources/assets.manifest
+    pub fn new(canvas_id: String, canvas_width: i32, canvas_height: i32, manifest_text: String, game_mode: u8, difficulty: u8) -> Result<FoxGame, JsValue>
     {
         utils::set_panic_hook();
 
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document.get_element_by_id(&canvas_id).unwrap();
+        let document = web_sys::window().and_then(|x| x.document()).ok_or("No document to draw into")?;
+        let canvas = document.get_element_by_id(&canvas_id).ok_or(format!("Canvas {} not found", canvas_id))?;
         let canvas: web_sys::HtmlCanvasElement = canvas.dyn_into::<web_sys::HtmlCanvasElement>()?;
     
         let context = canvas
             .get_context("webgl2")?
-            .unwrap()
+            .ok_or("WebGL2 is not supported")?
             .dyn_into::<WebGl2RenderingContext>()?;
 
-        context.viewport(0, 0, canvas_width, canvas_height);
-    
-        let tile_map = image::load_from_memory_with_format(&tile_map_raw_data, image::ImageFormat::Bmp).unwrap().to_rgba();
-        let sprite_tile_map = image::load_from_memory_with_format(&sprite_tile_map_raw_data, image::ImageFormat::Bmp).unwrap().to_rgba();
-        let game = for_fox_sake::ForFoxSake::new(context, tile_map, sprite_tile_map, canvas_width, canvas_height)?;
-
+        let manifest = assets::AssetManifest::parse(&manifest_text)?;
 
         Ok(FoxGame {
-            game: game,
+            game: None,
+            assets: assets::AssetStore::new(manifest),
+            context,
+            canvas_width,
+            canvas_height,
+            game_mode: model::game_mode::GameMode::from(game_mode)?,
+            difficulty: model::game_config::Difficulty::from(difficulty)?,
+            screen_shake_enabled: true,
+            scale_mode: view::scale_mode::ScaleMode::Letterbox,
+            render_resolution: None,
+            texture_filter: view::texture_filter::TextureFilter::Linear,
+            post_process_effect_order: None,
+            disabled_post_process_effects: std::collections::HashSet::new(),
+            pending_level: None,
+            pending_new_run: false,
         })
     }
 
+    //The level whose assets are needed right now
+    fn get_level_key(&self) -> String
+    {
+        match (&self.game, self.pending_level)
+        {
+            (_, Some((level_code, _))) => self.game_mode.get_level_key(level_code),
+            (Some(game), None) => game.get_level_key(),
+            (None, None) => self.game_mode.get_level_key(0),
+        }
+    }
+
+    //Name as given in the manifest. The game starts as soon as the last asset it needs arrives.
+    pub fn provide_asset(&mut self, name: String, bytes: std::vec::Vec<u8>) -> Result<(), JsValue>
+    {
+        self.assets.provide(&name, bytes)?;
+
+        if self.game.is_some()
+        {
+            self.load_pending_level()?;
+        }
+        else if self.assets.is_complete(&self.get_level_key())
+        {
+            let mut game = self.start()?;
+            game.set_screen_shake_enabled(self.screen_shake_enabled);
+            game.set_scale_mode(self.scale_mode)?;
+            game.set_render_resolution(self.render_resolution)?;
+            game.set_texture_filter(self.texture_filter);
+            if let Some(order) = &self.post_process_effect_order
+            {
+                game.set_post_process_effect_order(order.clone());
+            }
+            for effect_type in self.disabled_post_process_effects.iter()
+            {
+                game.set_post_process_effect_enabled(*effect_type, false);
+            }
+            self.game = Some(game);
+        }
+        Ok(())
+    }
+
+    fn start(&self) -> Result<for_fox_sake::ForFoxSake, String>
+    {
+        let level_assets = self.assets.get_level_assets(&self.get_level_key())?;
+        let tile_atlas = FoxGame::parse_tile_atlas(level_assets.tile_atlas_descriptor)?;
+        let sprite_atlas = FoxGame::parse_sprite_atlas(level_assets.sprite_atlas_descriptor)?;
+
+        let mut config = model::game_config::GameConfig::from_difficulty(self.difficulty);
+        if let Some(config_text) = level_assets.config_text
+        {
+            config = model::game_config::GameConfig::parse(&config_text, config)?;
+        }
+
+        let settings = for_fox_sake::game_settings::GameSettings {
+            game_mode: self.game_mode,
+            config,
+            tile_atlas,
+            sprite_atlas,
+            personal_best_storage: Box::new(for_fox_sake::personal_best_storage::InMemoryPersonalBestStorage::new()),
+        };
+        for_fox_sake::ForFoxSake::new(self.context.clone(), level_assets.tile_map, level_assets.sprite_tile_map, self.canvas_width, self.canvas_height, settings)
+    }
+
+    //Loads another level of the game mode behind a transition, 0 = fade, 1 = iris, 2 = hole, see view/transition.rs. The level and
+    //its assets are loaded as soon as the last of them arrived, until then loading_progress and missing_assets are about that level.
+    pub fn change_level(&mut self, level_code: u8, transition: u8) -> Result<(), JsValue>
+    {
+        let kind = view::transition::TransitionKind::from(transition)?;
+        self.get_started_game()?;
+        self.pending_level = Some((level_code, kind));
+        self.load_pending_level()?;
+        Ok(())
+    }
+
+    //Starts the current level again behind a transition, with the assets of the level
+    pub fn restart_level(&mut self, transition: u8) -> Result<(), JsValue>
+    {
+        let level_code = self.get_started_game()?.get_level_code();
+        self.change_level(level_code, transition)
+    }
+
+    //Starts a new run from the first level behind a transition, e.g. after GameOver. Lives, score and splits start over.
+    pub fn start_run(&mut self, transition: u8) -> Result<(), JsValue>
+    {
+        self.get_started_game()?;
+        self.pending_new_run = true;
+        self.change_level(0, transition)
+    }
+
+    fn load_pending_level(&mut self) -> Result<(), String>
+    {
+        let (level_code, kind) = match self.pending_level
+        {
+            Some(pending_level) => pending_level,
+            None => return Ok(()),
+        };
+        let level_key = self.game_mode.get_level_key(level_code);
+        if !self.assets.is_complete(&level_key)
+        {
+            return Ok(());
+        }
+        self.pending_level = None;
+        let new_run = std::mem::replace(&mut self.pending_new_run, false);
+
+        let level_assets = self.assets.get_level_assets(&level_key)?;
+        let tile_atlas = FoxGame::parse_tile_atlas(level_assets.tile_atlas_descriptor)?;
+        let sprite_atlas = FoxGame::parse_sprite_atlas(level_assets.sprite_atlas_descriptor)?;
+        let difficulty = self.difficulty;
+        let game = self.get_started_game()?;
+
+        if new_run
+        {
+            game.start_run(level_code, kind)?;
+        }
+        else
+        {
+            game.change_level(level_code, kind)?;
+        }
+        game.set_tile_map(level_assets.tile_map, tile_atlas)?;
+        game.set_sprite_tile_map(level_assets.sprite_tile_map, sprite_atlas)?;
+        //The config of the level only holds overrides on top of the chosen difficulty, see resources/gameConfig.txt
+        game.set_difficulty(difficulty);
+        if let Some(config_text) = level_assets.config_text
+        {
+            game.set_config(&config_text)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_started(&self) -> bool
+    {
+        self.game.is_some()
+    }
+
+    //Share of the assets needed for the current level that arrived, from 0 to 1
+    pub fn loading_progress(&self) -> f32
+    {
+        self.assets.get_progress(&self.get_level_key())
+    }
+
+    //Names of the assets the current level still waits for
+    pub fn missing_assets(&self) -> js_sys::Array
+    {
+        self.assets.get_missing(&self.get_level_key()).iter().map(|entry| JsValue::from_str(&entry.name)).collect()
+    }
+
+    //File of the asset as given in the manifest
+    pub fn asset_file(&self, name: String) -> Option<String>
+    {
+        self.assets.get_entry(&name).map(|entry| entry.file.clone())
+    }
+
+    //Without a descriptor the atlas that matches the bundled tile map is used
+    fn parse_tile_atlas(tile_atlas_descriptor: Option<String>) -> Result<sprite_atlas::SpriteAtlas, String>
+    {
+        match tile_atlas_descriptor
+        {
+            Some(descriptor) => sprite_atlas::SpriteAtlas::parse(&descriptor),
+            None => sprite_atlas::SpriteAtlas::parse(include_str!("../resources/testTileMap.atlas")),
+        }
+    }
+
+    //Without a descriptor the atlas that matches the bundled sprite tile map is used
+    fn parse_sprite_atlas(sprite_atlas_descriptor: Option<String>) -> Result<sprite_atlas::SpriteAtlas, String>
+    {
+        match sprite_atlas_descriptor
+        {
+            Some(descriptor) => sprite_atlas::SpriteAtlas::parse(&descriptor),
+            None => sprite_atlas::SpriteAtlas::parse(include_str!("../resources/spriteTileMap.atlas")),
+        }
+    }
+
+    fn get_started_game(&mut self) -> Result<&mut for_fox_sake::ForFoxSake, String>
+    {
+        self.game.as_mut().ok_or_else(|| "The game has not started yet, not all assets have been provided".to_string())
+    }
+
     pub fn update(&mut self, delta_time: f32)
     {
-        self.game.update(delta_time);
+        if let Some(game) = &mut self.game
+        {
+            game.update(delta_time);
+        }
+    }
+
+    pub fn draw(&mut self) 
+    {
+        if let Some(game) = &mut self.game
+        {
+            game.draw();
+        }
+    }
+
+    //0 = easy, 1 = normal, 2 = hard
+    pub fn set_difficulty(&mut self, difficulty: u8) -> Result<(), JsValue>
+    {
+        self.difficulty = model::game_config::Difficulty::from(difficulty)?;
+        if let Some(game) = &mut self.game
+        {
+            game.set_difficulty(self.difficulty);
+        }
+        Ok(())
+    }
+
+    //Call after the size of the canvas changed, e.g. on a resize event of the window. The width and height
+    //are the size of the drawing buffer in pixels, i.e. canvas.width and canvas.height.
+    pub fn resize(&mut self, canvas_width: i32, canvas_height: i32) -> Result<(), JsValue>
+    {
+        self.canvas_width = canvas_width;
+        self.canvas_height = canvas_height;
+        if let Some(game) = &mut self.game
+        {
+            game.resize(canvas_width, canvas_height)?;
+        }
+        Ok(())
+    }
+
+    //0 = letterbox, 1 = integer scale, 2 = fill, see view/scale_mode.rs
+    pub fn set_scale_mode(&mut self, scale_mode: u8) -> Result<(), JsValue>
+    {
+        self.scale_mode = view::scale_mode::ScaleMode::from(scale_mode)?;
+        if let Some(game) = &mut self.game
+        {
+            game.set_scale_mode(self.scale_mode)?;
+        }
+        Ok(())
+    }
+
+    //Resolution the scene is rendered at before it is scaled up to the canvas, e.g. 320x180. 0x0 renders at the size of the canvas.
+    pub fn set_render_resolution(&mut self, width: i32, height: i32) -> Result<(), JsValue>
+    {
+        self.render_resolution = if width == 0 && height == 0 { None } else { Some((width, height)) };
+        if let Some(game) = &mut self.game
+        {
+            game.set_render_resolution(self.render_resolution)?;
+        }
+        Ok(())
+    }
+
+    //0 = nearest for crisp pixel art, 1 = linear, see view/texture_filter.rs
+    pub fn set_texture_filter(&mut self, texture_filter: u8) -> Result<(), JsValue>
+    {
+        self.texture_filter = view::texture_filter::TextureFilter::from(texture_filter)?;
+        if let Some(game) = &mut self.game
+        {
+            game.set_texture_filter(self.texture_filter);
+        }
+        Ok(())
+    }
+
+    //Starts an effect with the look it has for events, e.g. 2 with a duration of Infinity for a CRT theme
+    pub fn start_post_process_effect(&mut self, effect: u8, duration: f32) -> Result<(), JsValue>
+    {
+        let effect_type = view_models::PostProcessEffects::from(effect)?;
+        self.get_started_game()?.start_post_process_effect(effect_type, duration);
+        Ok(())
+    }
+
+    //Codes of the effects in the order they are applied, 0 = vignette, 1 = flash, 2 = crt, 3 = chromatic aberration,
+    //4 = pixelate, 5 = desaturate, see view_models.rs
+    pub fn set_post_process_effect_order(&mut self, order: std::vec::Vec<u8>) -> Result<(), JsValue>
+    {
+        let order = order.iter().map(|x| view_models::PostProcessEffects::from(*x)).collect::<Result<std::vec::Vec<_>, String>>()?;
+        if let Some(game) = &mut self.game
+        {
+            game.set_post_process_effect_order(order.clone());
+        }
+        self.post_process_effect_order = Some(order);
+        Ok(())
+    }
+
+    pub fn set_post_process_effect_enabled(&mut self, effect: u8, enabled: bool) -> Result<(), JsValue>
+    {
+        let effect_type = view_models::PostProcessEffects::from(effect)?;
+        if enabled
+        {
+            self.disabled_post_process_effects.remove(&effect_type);
+        }
+        else
+        {
+            self.disabled_post_process_effects.insert(effect_type);
+        }
+        if let Some(game) = &mut self.game
+        {
+            game.set_post_process_effect_enabled(effect_type, enabled);
+        }
+        Ok(())
+    }
+
+    //Accessibility setting, also works before the game has started
+    pub fn set_screen_shake_enabled(&mut self, enabled: bool)
+    {
+        self.screen_shake_enabled = enabled;
+        if let Some(game) = &mut self.game
+        {
+            game.set_screen_shake_enabled(enabled);
+        }
+    }
+
+    //Shakes the screen for something the game does not know about, e.g. a noise spike. Trauma is from 0 to 1 and adds up.
+    pub fn add_screen_shake(&mut self, trauma: f32)
+    {
+        if let Some(game) = &mut self.game
+        {
+            game.add_screen_shake(trauma);
+        }
+    }
+
+    //Contents of a config file, see resources/gameConfig.txt
+    pub fn set_config(&mut self, config_text: String) -> Result<(), JsValue>
+    {
+        self.get_started_game()?.set_config(&config_text)?;
+        Ok(())
+    }
+
+    //Any image format the image crate detects, e.g. bmp or png. The grid of the atlas tells how the image is divided into tiles.
+    pub fn set_tile_map(&mut self, tile_map_raw_data: std::vec::Vec<u8>, tile_atlas_descriptor: Option<String>) -> Result<(), JsValue>
+    {
+        let tile_map = assets::load_image(&tile_map_raw_data, "tile map")?;
+        let tile_atlas = FoxGame::parse_tile_atlas(tile_atlas_descriptor)?;
+        self.get_started_game()?.set_tile_map(tile_map, tile_atlas)?;
+        Ok(())
+    }
+
+    //Shows another tile of the tile map in the given cell of the level, counted from the top left
+    pub fn set_map_tile(&mut self, col: u32, row: u32, tile: i32) -> Result<(), JsValue>
+    {
+        self.get_started_game()?.set_map_tile(col, row, tile)?;
+        Ok(())
     }
 
-    pub fn draw(&self) 
+    pub fn set_sprite_tile_map(&mut self, sprite_tile_map_raw_data: std::vec::Vec<u8>, sprite_atlas_descriptor: Option<String>) -> Result<(), JsValue>
     {
-        self.game.draw();
+        let sprite_tile_map = assets::load_image(&sprite_tile_map_raw_data, "sprite tile map")?;
+        let sprite_atlas = FoxGame::parse_sprite_atlas(sprite_atlas_descriptor)?;
+        self.get_started_game()?.set_sprite_tile_map(sprite_tile_map, sprite_atlas)?;
+        Ok(())
     }
 
     pub fn key_down(&mut self, key_code: i32)
     {
-        self.game.key_down(key_code);
+        if let Some(game) = &mut self.game
+        {
+            game.key_down(key_code);
+        }
     }
 
     pub fn key_up(&mut self, key_code: i32)
     {
-        self.game.key_up(key_code);
+        if let Some(game) = &mut self.game
+        {
+            game.key_up(key_code);
+        }
+    }
+
+    pub fn survival_time(&self) -> f32
+    {
+        self.game.as_ref().map_or(0.0, |game| game.get_survival_time())
     }
-}
\ No newline at end of file
+
+    pub fn score(&self) -> u32
+    {
+        self.game.as_ref().map_or(0, |game| game.get_score())
+    }
+
+    pub fn level_completed(&self) -> bool
+    {
+        self.game.as_ref().is_some_and(|game| game.is_level_completed())
+    }
+
+    //"gold", "silver" or "bronze" once the level is over, undefined otherwise or if no rank was reached
+    pub fn rank(&self) -> Option<String>
+    {
+        self.game.as_ref().and_then(|game| game.get_rank())
+    }
+
+    //Run time in seconds, counted in simulation ticks
+    pub fn run_time(&self) -> f32
+    {
+        self.game.as_ref().map_or(0.0, |game| game.get_run_time())
+    }
+
+    //Seconds of every level completed in the run, each from the start of its level like the personal best
+    pub fn splits(&self) -> std::vec::Vec<f32>
+    {
+        self.game.as_ref().map_or(std::vec::Vec::new(), |game| game.get_splits())
+    }
+
+    pub fn personal_best_time(&self) -> Option<f32>
+    {
+        self.game.as_ref().and_then(|game| game.get_personal_best_time())
+    }
+
+    pub fn personal_best_splits(&self) -> std::vec::Vec<f32>
+    {
+        self.game.as_ref().map_or(std::vec::Vec::new(), |game| game.get_personal_best_splits())
+    }
+
+    pub fn hit_points(&self) -> i32
+    {
+        self.game.as_ref().map_or(0, |game| game.get_hit_points())
+    }
+
+    pub fn lives(&self) -> u32
+    {
+        self.game.as_ref().map_or(0, |game| game.get_lives())
+    }
+
+    //Names of the model events since the last call, e.g. "FoxDamaged", "FoxDied" or "GameOver"
+    pub fn take_events(&mut self) -> js_sys::Array
+    {
+        match &mut self.game
+        {
+            Some(game) => game.take_events().iter().map(|event| JsValue::from_str(event.get_name())).collect(),
+            None => js_sys::Array::new(),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::for_fox_sake::read_only_input::ReadOnlyInput;
-use crate::view_models::{LevelViewModel, SpritesViewModel, ParticlesViewModel, PostProcessViewModel, PostProcessEffect, PostProcessEffects};
+use crate::sprite_atlas::SpriteAtlas;
+use crate::view_models::{LevelViewModel, SpritesViewModel, ParticlesViewModel, LightsViewModel, PostProcessViewModel, CameraViewModel, PostProcessEffect, PostProcessEffects, PostProcessParameters};
 use cgmath;
 use cgmath::InnerSpace;
 pub mod level;
@@ -13,113 +14,234 @@ mod fox;
 use fox::Fox;
 mod animation_trait;
 use animation_trait::SpriteAnimation;
+pub mod animation_controller;
 mod particle_system;
 use particle_system::ParticleSystemMetaData;
 mod post_process_effect_meta_data;
 use post_process_effect_meta_data::PostProcessEffectMetaData;
+pub mod game_mode;
+use game_mode::GameMode;
+mod spawner;
+use spawner::Spawner;
+mod wave_schedule;
+use wave_schedule::{WaveSchedule, WaveProgress};
+mod collectible;
+use collectible::{Collectible, CollectibleKind};
+pub mod rank;
+use rank::{Rank, RankThresholds};
+pub mod speedrun;
+use speedrun::{SpeedrunTimer, PersonalBest};
+pub mod health;
+use health::Health;
+pub mod game_config;
+use game_config::GameConfig;
+pub mod model_event;
+use model_event::ModelEvent;
+pub mod camera;
+use camera::Camera;
+pub mod screen_shake;
+mod light;
+use light::Lighting;
 
 pub struct Model
 {
     player: Fox,
     fox_holes: std::vec::Vec<FoxHole<cgmath::Vector2<f32>>>,
-    wolves: std::vec::Vec<Wolf<cgmath::Vector2<f32>>>,
+    wolves: std::vec::Vec<Wolf>,
     particle_systems: std::vec::Vec<ParticleSystemMetaData>,
     post_process_effects: std::vec::Vec<PostProcessEffectMetaData>,
     alive: bool,
+    mode: GameMode,
+    spawners: std::vec::Vec<Spawner<cgmath::Vector2<f32>>>,
+    wave_schedule: Option<WaveSchedule>,
+    wave_progress: WaveProgress,
+    hole_respawn_time: Option<f32>,
+    survival_time: f32,
+    score: u32,
+    collectibles: std::vec::Vec<Collectible<cgmath::Vector2<f32>>>,
+    par_time: f32,
+    rank_thresholds: Option<RankThresholds>,
+    level_time: f32,
+    holes_used: u32,
+    level_completed: bool,
+    rank: Option<Rank>,
+    level_code: u8,
+    speedrun: SpeedrunTimer,
+    ghost: Option<std::vec::Vec<cgmath::Vector2<f32>>>,
+    config: GameConfig,
+    lives: u32,
+    start_pos: cgmath::Vector2<f32>,
+    events: std::vec::Vec<ModelEvent>,
+    sprite_atlas: SpriteAtlas,
+    camera: Camera,
+    lighting: Option<Lighting<cgmath::Vector2<f32>>>,
+    //Drives the flicker of the lights
+    light_time: f32,
 }
 
 //Yes this is clunky with the identifiers at the back, but local variables are not supported by macros anymore
 //Could move the identifiers into model, but they don´t really fit there either...
 macro_rules! add_sprite {
-    ($x:expr, $y:expr, $pos:expr, $sprite:expr, $ss:ident, $sp:ident, $stmi:ident, $ci:ident) => {
-        $ss[$ci] = cgmath::Vector2{ x: $x, y: $y };
-        $sp[$ci] = $pos;
-        $stmi[$ci] = $sprite;
-        $ci = $ci + 1;
+    ($x:expr, $y:expr, $pos:expr, $sprite:expr, $ss:ident, $sp:ident, $stmi:ident, $sa:ident) => {
+        add_sprite!($x, $y, $pos, $sprite, 1.0, $ss, $sp, $stmi, $sa);
+    };
+    ($x:expr, $y:expr, $pos:expr, $sprite:expr, $alpha:expr, $ss:ident, $sp:ident, $stmi:ident, $sa:ident) => {
+        $ss.push(cgmath::Vector2{ x: $x, y: $y });
+        $sp.push($pos);
+        $stmi.push($sprite);
+        $sa.push($alpha);
     };
 }
 macro_rules! add_particle_effect {
-    ($pos:expr, $max_speed:expr, $time_passed:expr, $max_running_time:expr, $psp:ident, $psms:ident, $pstp:ident, $psmrt:ident, $ci:ident) => {
-        $psp[$ci] = $pos;
-        $psms[$ci] = $max_speed;
-        $pstp[$ci] = $time_passed;
-        $psmrt[$ci] = $max_running_time;
-        $ci = $ci + 1;
+    ($pos:expr, $max_speed:expr, $time_passed:expr, $max_running_time:expr, $psp:ident, $psms:ident, $pstp:ident, $psmrt:ident) => {
+        $psp.push($pos);
+        $psms.push($max_speed);
+        $pstp.push($time_passed);
+        $psmrt.push($max_running_time);
     };
 }
 
 impl Model
 {
-    pub fn new() -> Result<Model, String>
+    //The model is always advanced in steps of this size, see ForFoxSake::update
+    pub const TICK_DURATION: f32 = 1.0 / 60.0;
+    const FOX_LIGHT_COLOR: cgmath::Vector3<f32> = cgmath::Vector3 { x: 1.0, y: 0.9, z: 0.75 };
+    const HOLE_LIGHT_COLOR: cgmath::Vector3<f32> = cgmath::Vector3 { x: 0.4, y: 1.0, z: 0.6 };
+
+    pub fn new(mode: GameMode, config: GameConfig, sprite_atlas: &SpriteAtlas) -> Result<Model, String>
     {
+        Model::check_sprite_atlas(sprite_atlas)?;
+
         Ok(Model{ 
-            player: Fox::new(cgmath::Vector2 { x: 0.0, y: 0.0 }),
+            player: Fox::new(cgmath::Vector2 { x: 0.0, y: 0.0 }, config.max_hit_points, config.fox_animation_frame_time, sprite_atlas)?,
             fox_holes: std::vec::Vec::new(),
             wolves: std::vec::Vec::new(),
             particle_systems: std::vec::Vec::new(),
             post_process_effects: std::vec::Vec::new(),
             alive: true,
+            mode,
+            spawners: std::vec::Vec::new(),
+            wave_schedule: None,
+            wave_progress: WaveProgress::new(),
+            hole_respawn_time: None,
+            survival_time: 0.0,
+            score: 0,
+            collectibles: std::vec::Vec::new(),
+            par_time: 0.0,
+            rank_thresholds: None,
+            level_time: 0.0,
+            holes_used: 0,
+            level_completed: false,
+            rank: None,
+            level_code: 0,
+            speedrun: SpeedrunTimer::new(),
+            ghost: None,
+            lives: config.lives,
+            config,
+            start_pos: cgmath::Vector2 { x: 0.0, y: 0.0 },
+            events: std::vec::Vec::new(),
+            sprite_atlas: sprite_atlas.clone(),
+            camera: Camera::new(),
+            lighting: None,
+            light_time: 0.0,
         })
     }
 
 
-    pub fn to_sprites_view_model(&self) -> SpritesViewModel
+    //Wolves of endless waves are created in the middle of a run, so make sure all animations exist up front
+    pub fn check_sprite_atlas(sprite_atlas: &SpriteAtlas) -> Result<(), String>
+    {
+        Fox::new(cgmath::Vector2 { x: 0.0, y: 0.0 }, 1, 1.0, sprite_atlas)?;
+        Wolf::hunter(cgmath::Vector2 { x: 0.0, y: 0.0 }, 0.0, sprite_atlas)?;
+
+        let hole = FoxHole::new((), (), None);
+        for sprite in [ hole.entry_sprite, hole.exit_sprite, hole.closed_sprite, CollectibleKind::Berry.get_sprite(), CollectibleKind::Egg.get_sprite() ]
+        {
+            sprite_atlas.get_frame_index(sprite)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_sprite_atlas(&mut self, sprite_atlas: SpriteAtlas) -> Result<(), String>
+    {
+        Model::check_sprite_atlas(&sprite_atlas)?;
+
+        self.player.set_sprite_atlas(&sprite_atlas)?;
+        for wolf in self.wolves.iter_mut()
+        {
+            wolf.set_sprite_atlas(&sprite_atlas)?;
+        }
+        self.sprite_atlas = sprite_atlas;
+        Ok(())
+    }
+
+    pub fn to_sprites_view_model(&self) -> Result<SpritesViewModel, String>
     {
-        let mut sprite_sizes: [cgmath::Vector2<f32>;10] = [cgmath::Vector2{ x: 0.0, y: 0.0 };10];
-        let mut sprite_positions: [cgmath::Vector2<f32>;10] = [cgmath::Vector2{ x: 0.0, y: 0.0 };10];
-        let mut sprite_tile_map_indices: [i32;10] = [0;10];
+        let mut sprite_sizes: std::vec::Vec<cgmath::Vector2<f32>> = std::vec::Vec::new();
+        let mut sprite_positions: std::vec::Vec<cgmath::Vector2<f32>> = std::vec::Vec::new();
+        let mut sprite_frames: std::vec::Vec<usize> = std::vec::Vec::new();
+        let mut sprite_alphas: std::vec::Vec<f32> = std::vec::Vec::new();
 
-        let mut current_index = 0;
+        let sprite_size = self.config.sprite_size;
+        let closed_hole_size = self.config.closed_hole_size;
+        let collectible_size = self.config.collectible_size;
 
         for hole in self.fox_holes.iter()
         {
-            add_sprite!(0.2, 0.2, hole.entry, hole.entry_sprite, sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
-            add_sprite!(0.2, 0.2, hole.exit, hole.exit_sprite, sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
+            add_sprite!(sprite_size, sprite_size, hole.entry, self.sprite_atlas.get_frame_index(hole.entry_sprite)?, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
+            add_sprite!(sprite_size, sprite_size, hole.exit, self.sprite_atlas.get_frame_index(hole.exit_sprite)?, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
 
             if hole.used
             {
-                add_sprite!(0.15, 0.15, hole.entry, hole.closed_sprite, sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
-                add_sprite!(0.15, 0.15, hole.exit, hole.closed_sprite, sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
+                add_sprite!(closed_hole_size, closed_hole_size, hole.entry, self.sprite_atlas.get_frame_index(hole.closed_sprite)?, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
+                add_sprite!(closed_hole_size, closed_hole_size, hole.exit, self.sprite_atlas.get_frame_index(hole.closed_sprite)?, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
             }
         }
 
+        for collectible in self.collectibles.iter().filter(|x| !x.collected)
+        {
+            add_sprite!(collectible_size, collectible_size, collectible.pos, self.sprite_atlas.get_frame_index(collectible.kind.get_sprite())?, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
+        }
+
         for wolf in self.wolves.iter()
         {
-            add_sprite!(0.2, 0.2, wolf.pos, wolf.sprite, sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
+            add_sprite!(sprite_size, sprite_size, wolf.pos, wolf.get_frame(), sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
+        }
+
+        if let Some(ghost_pos) = self.get_ghost_position()
+        {
+            add_sprite!(sprite_size, sprite_size, ghost_pos, self.player.get_frame(), self.config.ghost_alpha, sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
         }
 
-        add_sprite!(0.2, 0.2, self.player.pos, self.player.get_sprite(), sprite_sizes, sprite_positions, sprite_tile_map_indices, current_index);
+        add_sprite!(sprite_size, sprite_size, self.player.pos, self.player.get_frame(), sprite_sizes, sprite_positions, sprite_frames, sprite_alphas);
 
-        SpritesViewModel {
+        Ok(SpritesViewModel {
             sizes: sprite_sizes,
             positions: sprite_positions,
-            tile_map_indices: sprite_tile_map_indices,
-            count: current_index as i32,
-        }
+            frames: sprite_frames,
+            alphas: sprite_alphas,
+        })
     }
 
     
     pub fn to_particles_view_model(&self) -> ParticlesViewModel
     {
-        let mut positions: [cgmath::Vector2<f32>;10] = [cgmath::Vector2{ x: 0.0, y: 0.0 };10];
-        let mut max_speeds: [f32;10] = [0.0;10];
-        let mut running_times: [f32;10] = [0.0;10];
-        let mut max_running_times: [f32;10] = [0.0;10];
-
-        let mut current_index = 0;
+        let mut positions: std::vec::Vec<cgmath::Vector2<f32>> = std::vec::Vec::new();
+        let mut max_speeds: std::vec::Vec<f32> = std::vec::Vec::new();
+        let mut running_times: std::vec::Vec<f32> = std::vec::Vec::new();
+        let mut max_running_times: std::vec::Vec<f32> = std::vec::Vec::new();
 
         for system in self.particle_systems.iter()
         {
-            add_particle_effect!(system.position, system.max_speed, system.running_time, system.max_running_time, positions, max_speeds, running_times, max_running_times, current_index);
+            add_particle_effect!(system.position, system.max_speed, system.running_time, system.max_running_time, positions, max_speeds, running_times, max_running_times);
         }
         
 
         ParticlesViewModel {
-            positions: positions,
-            max_speeds: max_speeds,
-            running_times: running_times,
-            max_running_times: max_running_times,
-            count: current_index as i32,
+            positions,
+            max_speeds,
+            running_times,
+            max_running_times,
         }
     }
 
@@ -133,6 +255,7 @@ impl Model
                 name: effect.name,
                 running_time: effect.running_time,
                 max_running_time: effect.max_running_time,
+                parameters: effect.parameters,
             });
         }
 
@@ -141,14 +264,98 @@ impl Model
         }
     }
 
+    //None for levels in daylight, which need no lighting pass
+    pub fn to_lights_view_model(&self) -> Option<LightsViewModel>
+    {
+        let lighting = self.lighting.as_ref()?;
+
+        let mut positions: std::vec::Vec<cgmath::Vector2<f32>> = std::vec::Vec::new();
+        let mut radii: std::vec::Vec<f32> = std::vec::Vec::new();
+        let mut colors: std::vec::Vec<cgmath::Vector3<f32>> = std::vec::Vec::new();
+        let mut intensities: std::vec::Vec<f32> = std::vec::Vec::new();
+
+        if lighting.fox_radius > 0.0
+        {
+            positions.push(self.player.pos);
+            radii.push(lighting.fox_radius);
+            colors.push(Model::FOX_LIGHT_COLOR);
+            intensities.push(1.0);
+        }
+
+        if lighting.hole_radius > 0.0
+        {
+            for hole in self.fox_holes.iter().filter(|x| !x.used)
+            {
+                for pos in [ hole.entry, hole.exit ].iter()
+                {
+                    positions.push(*pos);
+                    radii.push(lighting.hole_radius);
+                    colors.push(Model::HOLE_LIGHT_COLOR);
+                    intensities.push(1.0);
+                }
+            }
+        }
+
+        for (i, light) in lighting.lights.iter().enumerate()
+        {
+            positions.push(light.pos);
+            radii.push(light.radius);
+            colors.push(light.color);
+            intensities.push(light.get_intensity(i, self.light_time));
+        }
+
+        Some(LightsViewModel {
+            ambient: lighting.ambient,
+            positions,
+            radii,
+            colors,
+            intensities,
+            occluding_tiles: lighting.occluding_tiles.clone(),
+        })
+    }
+
+    pub fn to_camera_view_model(&self) -> CameraViewModel
+    {
+        self.camera.to_view_model(&self.config)
+    }
+
+    //Shakes the screen for things the model does not know about, e.g. a loud noise
+    pub fn add_trauma(&mut self, amount: f32)
+    {
+        self.camera.add_trauma(amount);
+    }
+
+    pub fn set_screen_shake_enabled(&mut self, enabled: bool)
+    {
+        self.camera.set_shake_enabled(enabled);
+    }
+
+    //Starts over with all lives, no score and a new speedrun, e.g. once the game is over. Load a level afterwards.
+    pub fn start_run(&mut self)
+    {
+        self.alive = true;
+        self.lives = self.config.lives;
+        self.score = 0;
+        self.survival_time = 0.0;
+        self.speedrun = SpeedrunTimer::new();
+    }
+
     pub fn load_level(&mut self, level_code: u8) -> Result<LevelViewModel, String>
     {
-        let level = match level_code
+        let level = match (self.mode, level_code)
         {
-            0 => Ok(level::Levels::level_0()),
-            _ => Err(format!("Level not found")),
+            (GameMode::Campaign, 0) => Ok(level::Levels::level_0()),
+            (GameMode::Campaign, 1) => Ok(level::Levels::level_1()),
+            (GameMode::Endless, 0) => Ok(level::Levels::endless_0()),
+            _ => Err("Level not found".to_string()),
         }?;
-        
+
+        //The last wave is repeated once the schedule is used up, so there has to be one
+        if matches!(level.get_wave_schedule(), Some(schedule) if schedule.waves.is_empty())
+        {
+            return Err(format!("The wave schedule of level {} has no waves", level_code));
+        }
+
         let check_width = level.get_data()[0].len();
         let width = check_width as f32;
         let height = level.get_data().len() as f32;
@@ -159,11 +366,18 @@ impl Model
             assert_eq!(check_width, row.len());
             for tile in row.iter()
             {
-                flat_map.push(tile.clone());
+                flat_map.push(*tile);
             }
         }
 
-        self.player.pos = model_utils::grid_to_position(level.get_start_pos(), width, height);
+        self.start_pos = model_utils::grid_to_position(level.get_start_pos(), width, height);
+        self.player.pos = self.start_pos;
+        self.player.health = Health::new(self.config.max_hit_points);
+
+        self.fox_holes.clear();
+        self.wolves.clear();
+        self.spawners.clear();
+        self.collectibles.clear();
 
         for hole in level.get_fox_holes().iter()
         {
@@ -172,13 +386,46 @@ impl Model
 
         for wolf in level.get_wolves().iter()
         {
-            self.wolves.push(Wolf::from(wolf, width, height));
+            self.wolves.push(Wolf::from(wolf, width, height, &self.sprite_atlas)?);
+        }
+
+        for spawner in level.get_spawners().iter()
+        {
+            self.spawners.push(Spawner::from(spawner, width, height));
         }
 
+        self.wave_schedule = level.get_wave_schedule().clone();
+        self.wave_progress = WaveProgress::new();
+        self.hole_respawn_time = level.get_hole_respawn_time();
+
+        for collectible in level.get_collectibles().iter()
+        {
+            self.collectibles.push(Collectible::from(collectible, width, height));
+        }
+
+        self.lighting = level.get_lighting().as_ref().map(|x| Lighting::from(x, width, height));
+        self.light_time = 0.0;
+
+        self.par_time = level.get_par_time();
+        self.rank_thresholds = Some(level.get_rank_thresholds().clone());
+        self.level_time = 0.0;
+        self.holes_used = 0;
+        self.level_completed = false;
+        self.rank = None;
+        self.level_code = level_code;
+        self.speedrun.start_level();
+        self.ghost = None;
+
+        //The level fills the area from -1 to 1, however big its map is
+        self.camera.set_bounds(cgmath::Vector2 { x: -1.0, y: -1.0 }, cgmath::Vector2 { x: 1.0, y: 1.0 });
+        self.camera.snap_to(self.start_pos, &self.config);
+        //Every run of a level shakes the same way for the same events
+        self.camera.reset_shake(level_code as u32);
+
         Ok(LevelViewModel {
             data: flat_map,
-            width: width,
-            height: height,
+            width,
+            height,
         })
     }
 
@@ -186,49 +433,72 @@ impl Model
     {
         self.particle_systems.push(ParticleSystemMetaData{
             position: start_position,
-            max_speed: 0.1,
+            max_speed: self.config.hole_particles_max_speed,
             running_time: 0.0,
-            max_running_time: 3.0,
+            max_running_time: self.config.hole_particles_lifetime,
         });
     }
 
     fn spawn_fox_hole_entry_post_process_effect(&mut self)
+    {
+        self.start_post_process_effect(PostProcessEffects::VIGNETTE, self.config.hole_vignette_duration, PostProcessParameters::default_for(PostProcessEffects::VIGNETTE));
+    }
+
+    //E.g. a red vignette whose strength eases in over 0.3 seconds
+    pub fn start_post_process_effect(&mut self, name: PostProcessEffects, duration: f32, parameters: PostProcessParameters)
     {
         self.post_process_effects.push(PostProcessEffectMetaData{ 
-            name: PostProcessEffects::VIGNETTE,
+            name,
             running_time: 0.0,
-            max_running_time: 1.5,
+            max_running_time: duration,
+            parameters,
         });
     }
 
-    fn move_player(&mut self, input: &ReadOnlyInput, delta_time: f32)
+    fn move_player(&mut self, input: &ReadOnlyInput, delta_time: f32) -> Result<(), String>
     {
-        const SPEED: f32 = 0.2;
+        let speed = self.config.fox_speed;
+
+        //No control over the fox while it is thrown back by a bite
+        if self.player.health.is_knocked_back()
+        {
+            self.player.velocity = self.player.health.knockback;
+            self.player.pos += self.player.velocity * delta_time;
+            return Ok(());
+        }
 
         let move_left = input.is_input_down("MoveLeft") || input.is_input_pressed("MoveLeft");
         let move_right = input.is_input_down("MoveRight") || input.is_input_pressed("MoveRight");
         let move_down = input.is_input_down("MoveDown") || input.is_input_pressed("MoveDown");
         let move_up = input.is_input_down("MoveUp") || input.is_input_pressed("MoveUp");
 
+        let mut direction = cgmath::Vector2 { x: 0.0, y: 0.0 };
         if move_left && !move_right
         {
-            self.player.pos.x -= SPEED * delta_time;
-            self.player.update_animation(Fox::MOVE_LEFT, delta_time);
+            direction.x -= 1.0;
         } 
         else if move_right && !move_left
         {
-            self.player.pos.x += SPEED * delta_time;
-            self.player.update_animation(Fox::MOVE_RIGHT, delta_time);
+            direction.x += 1.0;
         } 
-        else if move_down && !move_up
+
+        if move_down && !move_up
         {
-            self.player.pos.y -= SPEED * delta_time;
-            self.player.update_animation(Fox::MOVE_DOWN, delta_time);
+            direction.y -= 1.0;
         }
         else if move_up && !move_down
         {
-            self.player.pos.y += SPEED * delta_time;
-            self.player.update_animation(Fox::MOVE_UP, delta_time);
+            direction.y += 1.0;
+        }
+
+        self.player.walk(direction, speed, delta_time)
+    }
+
+    fn collect_animation_events(&mut self)
+    {
+        for event in self.player.get_animation_controller_mut().take_events()
+        {
+            self.events.push(ModelEvent::AnimationEvent { name: event });
         }
     }
 
@@ -244,10 +514,11 @@ impl Model
                     continue;
                 }
 
-                if (self.player.pos - hole.entry).magnitude() < 0.1
+                if (self.player.pos - hole.entry).magnitude() < self.config.hole_use_radius
                 {
                     self.player.pos = hole.exit;
                     hole.used = true;
+                    self.holes_used += 1;
                     used_entry_position = Some(hole.entry);
                 }
             }
@@ -255,6 +526,8 @@ impl Model
 
         if let Some(pos) = used_entry_position
         {
+            self.player.dive();
+            self.camera.add_trauma(self.config.dive_trauma);
             self.spawn_fox_hole_entry_particle_system(pos);
             self.spawn_fox_hole_entry_post_process_effect();
         }
@@ -262,11 +535,181 @@ impl Model
 
     fn check_wolves(&mut self)
     {
-        for wolf in &mut self.wolves
+        if !self.player.health.is_invulnerable()
         {
-            if (self.player.pos - wolf.pos).magnitude() < 0.15
+            let player_pos = self.player.pos;
+            let bite_radius = self.config.wolf_bite_radius;
+            let biting_wolf = self.wolves.iter().find(|wolf| (player_pos - wolf.pos).magnitude() < bite_radius);
+
+            if let Some(wolf) = biting_wolf
             {
-                self.alive = false;
+                let wolf_pos = wolf.pos;
+                self.bite_player(wolf_pos);
+            }
+        }
+
+        //There is no goal in endless mode, so the run is ranked once the fox is caught
+        if !self.alive && self.mode == GameMode::Endless
+        {
+            self.rank = self.get_rank_for_score();
+        }
+    }
+
+    fn bite_player(&mut self, wolf_pos: cgmath::Vector2<f32>)
+    {
+        let health = &mut self.player.health;
+        health.hit_points -= 1;
+        health.invulnerable_time_left = self.config.invulnerability_time;
+
+        let away_from_wolf = self.player.pos - wolf_pos;
+        if away_from_wolf.magnitude() > 0.0
+        {
+            health.knockback = away_from_wolf.normalize() * self.config.knockback_speed;
+            health.knockback_time_left = self.config.knockback_time;
+        }
+
+        let hit_points = health.hit_points;
+        self.camera.add_trauma(self.config.bite_trauma);
+
+        //A lethal bite is only reported as death, so the page and the view react to it once
+        if hit_points <= 0
+        {
+            self.kill_player();
+        }
+        else
+        {
+            self.events.push(ModelEvent::FoxDamaged { hit_points });
+        }
+    }
+
+    fn kill_player(&mut self)
+    {
+        self.lives = self.lives.saturating_sub(1);
+        self.events.push(ModelEvent::FoxDied { lives_left: self.lives });
+
+        if self.lives == 0
+        {
+            self.alive = false;
+            self.events.push(ModelEvent::GameOver);
+            return;
+        }
+
+        self.player.pos = self.start_pos;
+        self.player.health = Health::new(self.config.max_hit_points);
+        self.player.health.invulnerable_time_left = self.config.invulnerability_time;
+    }
+
+    fn check_collectibles(&mut self)
+    {
+        for collectible in self.collectibles.iter_mut().filter(|x| !x.collected)
+        {
+            if (self.player.pos - collectible.pos).magnitude() < self.config.collect_radius
+            {
+                collectible.collected = true;
+                self.score += collectible.kind.get_points();
+            }
+        }
+
+        if self.mode == GameMode::Campaign && !self.collectibles.is_empty() && self.collectibles.iter().all(|x| x.collected)
+        {
+            self.complete_level();
+        }
+    }
+
+    fn get_ghost_position(&self) -> Option<cgmath::Vector2<f32>>
+    {
+        match &self.ghost
+        {
+            Some(ghost) if !ghost.is_empty() =>
+            {
+                let index = std::cmp::min(self.speedrun.get_level_ticks() as usize, ghost.len() - 1);
+                Some(ghost[index])
+            },
+            _ => None,
+        }
+    }
+
+    fn complete_level(&mut self)
+    {
+        self.level_completed = true;
+        self.speedrun.split();
+
+        if self.level_time < self.par_time
+        {
+            self.score += ((self.par_time - self.level_time) * self.config.speed_bonus_per_second) as u32;
+        }
+
+        if self.holes_used == 0
+        {
+            self.score += self.config.no_holes_bonus;
+        }
+
+        self.rank = self.get_rank_for_score();
+    }
+
+    fn get_rank_for_score(&self) -> Option<Rank>
+    {
+        match &self.rank_thresholds
+        {
+            Some(thresholds) => thresholds.get_rank(self.score),
+            None => None,
+        }
+    }
+
+    fn update_wolves(&mut self, delta_time: f32)
+    {
+        for wolf in self.wolves.iter_mut()
+        {
+            if self.config.placed_wolves_hunt
+            {
+                wolf.watch(self.player.pos, self.config.wolf_detection_range, self.config.wolf_reaction_time, delta_time);
+            }
+            wolf.hunt(self.player.pos, self.config.wolf_speed, delta_time);
+        }
+
+        self.wolves.retain(|x| !x.is_expired());
+    }
+
+    fn update_waves(&mut self, delta_time: f32) -> Result<(), String>
+    {
+        if let Some(schedule) = &self.wave_schedule
+        {
+            if self.spawners.is_empty()
+            {
+                return Ok(());
+            }
+
+            let waves_completed = self.wave_progress.waves_completed;
+            if self.wave_progress.update(schedule, delta_time, self.wolves.len())
+            {
+                let spawner = &self.spawners[self.wave_progress.total_spawned as usize % self.spawners.len()];
+                self.wolves.push(Wolf::hunter(spawner.pos, schedule.wolf_lifetime, &self.sprite_atlas)?);
+            }
+            self.score += (self.wave_progress.waves_completed - waves_completed) * self.config.wave_bonus;
+        }
+        Ok(())
+    }
+
+    fn update_survival_time(&mut self, delta_time: f32)
+    {
+        let full_seconds_before = self.survival_time.floor();
+        self.survival_time += delta_time;
+        self.score += (self.survival_time.floor() - full_seconds_before) as u32 * self.config.survival_points_per_second;
+    }
+
+    fn update_fox_holes(&mut self, delta_time: f32)
+    {
+        if let Some(respawn_time) = self.hole_respawn_time
+        {
+            let respawn_time = respawn_time * self.config.hole_cooldown_factor;
+            for hole in self.fox_holes.iter_mut().filter(|x| x.used)
+            {
+                hole.time_since_used += delta_time;
+                if hole.time_since_used >= respawn_time
+                {
+                    hole.used = false;
+                    hole.time_since_used = 0.0;
+                }
             }
         }
     }
@@ -275,7 +718,7 @@ impl Model
     {
         for system in self.particle_systems.iter_mut()
         {
-            system.running_time = system.running_time + delta_time;
+            system.running_time += delta_time;
         }
 
         self.particle_systems.retain(|x| x.running_time < x.max_running_time);
@@ -285,21 +728,135 @@ impl Model
     {
         for effect in self.post_process_effects.iter_mut()
         {
-            effect.running_time = effect.running_time + delta_time;
+            effect.running_time += delta_time;
         }
 
         self.post_process_effects.retain(|x| x.running_time < x.max_running_time);
     }
 
-    pub fn update(&mut self, input: ReadOnlyInput, delta_time: f32)
+    pub fn update(&mut self, input: ReadOnlyInput, delta_time: f32) -> Result<(), String>
     {
-        if self.alive
+        if self.alive && !self.level_completed
         {
+            self.level_time += delta_time;
+            self.speedrun.tick();
+            self.player.health.update(delta_time);
             self.check_fox_hole_usage(&input);
-            self.move_player(&input, delta_time);
+            self.move_player(&input, delta_time)?;
+            self.collect_animation_events();
+            self.update_wolves(delta_time);
+            if self.mode == GameMode::Endless
+            {
+                self.update_waves(delta_time)?;
+                self.update_survival_time(delta_time);
+            }
             self.check_wolves();
+            self.check_collectibles();
+            self.update_fox_holes(delta_time);
             self.update_particle_systems(delta_time);
             self.update_post_process_effects(delta_time);
+            //Only a completed campaign level keeps its recording as ghost
+            if self.mode == GameMode::Campaign
+            {
+                self.speedrun.record(self.player.pos);
+            }
         }
+
+        self.light_time += delta_time;
+        self.camera.update(self.player.pos, &self.config, delta_time);
+        Ok(())
+    }
+
+    pub fn get_survival_time(&self) -> f32
+    {
+        self.survival_time
+    }
+
+    pub fn get_score(&self) -> u32
+    {
+        self.score
+    }
+
+    pub fn is_level_completed(&self) -> bool
+    {
+        self.level_completed
+    }
+
+    pub fn get_rank(&self) -> Option<Rank>
+    {
+        self.rank
+    }
+
+    pub fn get_level_code(&self) -> u8
+    {
+        self.level_code
+    }
+
+    pub fn get_player_position(&self) -> cgmath::Vector2<f32>
+    {
+        self.player.pos
+    }
+
+    pub fn get_level_key(&self) -> String
+    {
+        self.mode.get_level_key(self.level_code)
+    }
+
+    pub fn get_run_ticks(&self) -> u64
+    {
+        self.speedrun.ticks
+    }
+
+    pub fn get_splits(&self) -> &std::vec::Vec<u64>
+    {
+        &self.speedrun.splits
+    }
+
+    pub fn get_finished_run(&self) -> PersonalBest
+    {
+        PersonalBest {
+            ticks: self.speedrun.get_level_ticks(),
+            splits: self.speedrun.splits.clone(),
+            ghost: self.speedrun.recording.clone(),
+        }
+    }
+
+    pub fn set_ghost(&mut self, ghost: Option<std::vec::Vec<cgmath::Vector2<f32>>>)
+    {
+        self.ghost = ghost;
+    }
+
+    pub fn get_hit_points(&self) -> i32
+    {
+        self.player.health.hit_points
+    }
+
+    pub fn is_game_over(&self) -> bool
+    {
+        !self.alive
+    }
+
+    pub fn get_lives(&self) -> u32
+    {
+        self.lives
+    }
+
+    pub fn take_events(&mut self) -> std::vec::Vec<ModelEvent>
+    {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn get_config(&self) -> &GameConfig
+    {
+        &self.config
+    }
+
+    //Keeps the current progress, only lives and hit points are capped to the new maximum
+    pub fn set_config(&mut self, config: GameConfig)
+    {
+        self.lives = std::cmp::min(self.lives, config.lives);
+        self.player.health.hit_points = std::cmp::min(self.player.health.hit_points, config.max_hit_points);
+        self.player.set_frame_time(config.fox_animation_frame_time);
+        self.config = config;
     }
 }
\ No newline at end of file
@@ -0,0 +1,294 @@
+use crate::sprite_atlas::SpriteAtlas;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum PlayMode
+{
+    OneShot,
+    Loop,
+    PingPong,
+}
+
+#[derive(Clone)]
+pub struct AnimationFrame
+{
+    //Index of the frame in the sprite atlas
+    pub frame: usize,
+    pub duration: f32,
+    //Reported through AnimationController::take_events when the frame is entered
+    pub event: Option<&'static str>,
+}
+
+#[derive(Clone)]
+pub struct Animation
+{
+    pub frames: std::vec::Vec<AnimationFrame>,
+    pub mode: PlayMode,
+}
+
+impl Animation
+{
+    //Frames of the atlas animation with that name, all shown for the same time
+    pub fn from_atlas(atlas: &SpriteAtlas, animation_name: &str, frame_duration: f32, mode: PlayMode) -> Result<Animation, String>
+    {
+        let frames = atlas.get_animation_frames(animation_name)?
+            .into_iter()
+            .map(|frame| AnimationFrame { frame, duration: frame_duration, event: None })
+            .collect();
+        Ok(Animation { frames, mode })
+    }
+
+    //Puts the event on the given frame, or on the last one if the animation is shorter
+    pub fn with_event(mut self, frame_index: usize, event: &'static str) -> Animation
+    {
+        let frame_index = std::cmp::min(frame_index, self.frames.len() - 1);
+        self.frames[frame_index].event = Some(event);
+        self
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum AnimationCondition
+{
+    //The current one shot animation reached its last frame
+    Finished,
+    Parameter(&'static str, bool),
+    //Like a parameter, but only valid for the update it was set in
+    Trigger(&'static str),
+}
+
+pub struct AnimationTransition
+{
+    pub from: &'static str,
+    pub to: &'static str,
+    pub condition: AnimationCondition,
+}
+
+pub struct AnimationController
+{
+    animations: std::collections::HashMap<&'static str, Animation>,
+    transitions: std::vec::Vec<AnimationTransition>,
+    parameters: std::collections::HashMap<&'static str, bool>,
+    triggers: std::vec::Vec<&'static str>,
+    events: std::vec::Vec<&'static str>,
+    current_animation: &'static str,
+    current_frame: usize,
+    frame_time: f32,
+    ping_pong_backwards: bool,
+    finished: bool,
+}
+
+impl AnimationController
+{
+    pub fn new(animations: std::collections::HashMap<&'static str, Animation>, start_animation: &'static str) -> Result<AnimationController, String>
+    {
+        if let Some((name, _)) = animations.iter().find(|(_, x)| x.frames.is_empty())
+        {
+            return Err(format!("Animation {} needs at least one frame", name));
+        }
+        if !animations.contains_key(start_animation)
+        {
+            return Err(format!("Start animation {} not found", start_animation));
+        }
+
+        Ok(AnimationController {
+            animations,
+            transitions: std::vec::Vec::new(),
+            parameters: std::collections::HashMap::new(),
+            triggers: std::vec::Vec::new(),
+            events: std::vec::Vec::new(),
+            current_animation: start_animation,
+            current_frame: 0,
+            frame_time: 0.0,
+            ping_pong_backwards: false,
+            finished: false,
+        })
+    }
+
+    pub fn add_transition(&mut self, from: &'static str, to: &'static str, condition: AnimationCondition) -> Result<(), String>
+    {
+        self.check_animation(from)?;
+        self.check_animation(to)?;
+        self.transitions.push(AnimationTransition { from, to, condition });
+        Ok(())
+    }
+
+    pub fn set_parameter(&mut self, name: &'static str, value: bool)
+    {
+        self.parameters.insert(name, value);
+    }
+
+    pub fn trigger(&mut self, name: &'static str)
+    {
+        self.triggers.push(name);
+    }
+
+    pub fn get_current_animation(&self) -> &'static str
+    {
+        self.current_animation
+    }
+
+    pub fn get_frame(&self) -> usize
+    {
+        self.animations[self.current_animation].frames[self.current_frame].frame
+    }
+
+    //Only the given animations, unknown names are skipped
+    pub fn scale_durations(&mut self, animation_names: &[&'static str], factor: f32)
+    {
+        for animation_name in animation_names.iter()
+        {
+            if let Some(animation) = self.animations.get_mut(animation_name)
+            {
+                for frame in animation.frames.iter_mut()
+                {
+                    frame.duration *= factor;
+                }
+            }
+        }
+
+        if animation_names.contains(&self.current_animation)
+        {
+            self.frame_time *= factor;
+        }
+    }
+
+    //One shot animations are not interrupted by SpriteAnimation::update_animation
+    pub fn is_playing_one_shot(&self) -> bool
+    {
+        self.animations[self.current_animation].mode == PlayMode::OneShot && !self.finished
+    }
+
+    pub fn take_events(&mut self) -> std::vec::Vec<&'static str>
+    {
+        std::mem::take(&mut self.events)
+    }
+
+    //Starts the animation from its first frame, even if it is already playing
+    pub fn play(&mut self, animation_name: &'static str) -> Result<(), String>
+    {
+        self.check_animation(animation_name)?;
+        self.start(animation_name);
+        Ok(())
+    }
+
+    fn check_animation(&self, animation_name: &str) -> Result<(), String>
+    {
+        if !self.animations.contains_key(animation_name)
+        {
+            return Err(format!("Animation {} not found", animation_name));
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, animation_name: &'static str)
+    {
+        self.current_animation = animation_name;
+        self.current_frame = 0;
+        self.frame_time = 0.0;
+        self.ping_pong_backwards = false;
+        self.finished = false;
+        self.push_frame_event();
+    }
+
+    pub fn update(&mut self, delta_time: f32)
+    {
+        self.frame_time += delta_time;
+
+        loop
+        {
+            let frame_duration = self.animations[self.current_animation].frames[self.current_frame].duration;
+            if self.finished || frame_duration <= 0.0 || self.frame_time < frame_duration
+            {
+                break;
+            }
+
+            self.frame_time -= frame_duration;
+            self.advance_frame();
+        }
+
+        self.check_transitions();
+        self.triggers.clear();
+    }
+
+    fn advance_frame(&mut self)
+    {
+        let animation = &self.animations[self.current_animation];
+        let last_frame = animation.frames.len() - 1;
+
+        match animation.mode
+        {
+            PlayMode::Loop =>
+            {
+                self.current_frame = (self.current_frame + 1) % animation.frames.len();
+            },
+            PlayMode::OneShot =>
+            {
+                if self.current_frame == last_frame
+                {
+                    self.finished = true;
+                    self.frame_time = 0.0;
+                    return;
+                }
+                self.current_frame += 1;
+            },
+            PlayMode::PingPong =>
+            {
+                if last_frame == 0
+                {
+                    return;
+                }
+                if self.current_frame == last_frame
+                {
+                    self.ping_pong_backwards = true;
+                }
+                else if self.current_frame == 0
+                {
+                    self.ping_pong_backwards = false;
+                }
+
+                if self.ping_pong_backwards
+                {
+                    self.current_frame -= 1;
+                }
+                else
+                {
+                    self.current_frame += 1;
+                }
+            },
+        }
+
+        self.push_frame_event();
+    }
+
+    fn push_frame_event(&mut self)
+    {
+        if let Some(event) = self.animations[self.current_animation].frames[self.current_frame].event
+        {
+            self.events.push(event);
+        }
+    }
+
+    fn check_transitions(&mut self)
+    {
+        let next_animation = self.transitions.iter()
+            .filter(|x| x.from == self.current_animation)
+            .find(|x| self.is_condition_met(&x.condition))
+            .map(|x| x.to);
+
+        //The animations of a transition were checked when it was added
+        if let Some(animation_name) = next_animation
+        {
+            self.start(animation_name);
+        }
+    }
+
+    fn is_condition_met(&self, condition: &AnimationCondition) -> bool
+    {
+        match condition
+        {
+            AnimationCondition::Finished => self.finished,
+            AnimationCondition::Parameter(name, value) => self.parameters.get(name).cloned().unwrap_or(false) == *value,
+            AnimationCondition::Trigger(name) => self.triggers.contains(name),
+        }
+    }
+}
@@ -1,34 +1,28 @@
-use crate::model::model_utils::SpriteAnimationMetaData;
+use crate::model::animation_controller::AnimationController;
 
 pub trait SpriteAnimation
 {
+    fn get_animation_controller(&self) -> &AnimationController;
+    fn get_animation_controller_mut(&mut self) -> &mut AnimationController;
 
-    fn get_sprite_animations(&self) -> &std::collections::HashMap<&'static str, SpriteAnimationMetaData>;
-    fn get_current_animation(&self) -> &'static str;
-    fn set_current_animation(&mut self, new_current_animation: &'static str);
-    fn get_animation_time(&self) -> f32;
-    fn set_animation_time(&mut self, new_animation_time: f32);
 
 
-
-    fn update_animation(&mut self, animation_name: &'static str, delta_time: f32)
+    fn update_animation(&mut self, animation_name: &'static str, delta_time: f32) -> Result<(), String>
     {
-        if animation_name == self.get_current_animation()
+        let controller = self.get_animation_controller_mut();
+        if animation_name == controller.get_current_animation() || controller.is_playing_one_shot()
         {
-            let animation = self.get_sprite_animations().get(&self.get_current_animation()).unwrap();
-            let max_time = (animation.to_index - animation.from_index) as f32 * animation.timeout;
-            self.set_animation_time((self.get_animation_time() + delta_time) % max_time);
+            controller.update(delta_time);
+            Ok(())
         }
         else
         {
-            self.set_animation_time(0.0);
-            self.set_current_animation(animation_name);
+            controller.play(animation_name)
         }
     }
 
-    fn get_sprite(&self) -> i32
+    fn get_frame(&self) -> usize
     {
-        let animation = self.get_sprite_animations().get(&self.get_current_animation()).unwrap();
-        animation.from_index + (self.get_animation_time() / animation.timeout).floor() as i32
+        self.get_animation_controller().get_frame()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,143 @@
+use crate::model::game_config::GameConfig;
+use crate::model::screen_shake::ScreenShake;
+use crate::view_models::CameraViewModel;
+
+use cgmath;
+
+//Decides which part of the level is on screen. The level spans from -1 to 1 in both directions, at a zoom of 1 it fills the screen.
+//The camera follows a target once it leaves the dead zone around the center of the screen and never shows anything outside the bounds.
+//Screen shake is added on top when the camera is handed to the view, so it does not change where the camera follows to.
+pub struct Camera
+{
+    pub position: cgmath::Vector2<f32>,
+    zoom: f32,
+    bounds_min: cgmath::Vector2<f32>,
+    bounds_max: cgmath::Vector2<f32>,
+    shake: ScreenShake,
+    //Accessibility setting, the shake keeps running while disabled, so enabling it again does not change a replay
+    shake_enabled: bool,
+}
+
+impl Camera
+{
+    const MIN_ZOOM: f32 = 0.01;
+
+    pub fn new() -> Camera
+    {
+        Camera {
+            position: cgmath::Vector2 { x: 0.0, y: 0.0 },
+            zoom: 1.0,
+            bounds_min: cgmath::Vector2 { x: -1.0, y: -1.0 },
+            bounds_max: cgmath::Vector2 { x: 1.0, y: 1.0 },
+            shake: ScreenShake::new(0),
+            shake_enabled: true,
+        }
+    }
+
+    //Starts without trauma, e.g. when a level starts
+    pub fn reset_shake(&mut self, seed: u32)
+    {
+        self.shake = ScreenShake::new(seed);
+    }
+
+    pub fn add_trauma(&mut self, amount: f32)
+    {
+        self.shake.add_trauma(amount);
+    }
+
+    pub fn get_trauma(&self) -> f32
+    {
+        self.shake.get_trauma()
+    }
+
+    pub fn set_shake_enabled(&mut self, enabled: bool)
+    {
+        self.shake_enabled = enabled;
+    }
+
+    pub fn set_bounds(&mut self, min: cgmath::Vector2<f32>, max: cgmath::Vector2<f32>)
+    {
+        self.bounds_min = min;
+        self.bounds_max = max;
+    }
+
+    //Centers the target without smoothing, e.g. when a level starts
+    pub fn snap_to(&mut self, target: cgmath::Vector2<f32>, config: &GameConfig)
+    {
+        self.zoom = config.camera_zoom.max(Camera::MIN_ZOOM);
+        self.position = self.clamp_to_bounds(target);
+    }
+
+    pub fn update(&mut self, target: cgmath::Vector2<f32>, config: &GameConfig, delta_time: f32)
+    {
+        self.zoom = config.camera_zoom.max(Camera::MIN_ZOOM);
+        self.shake.update(config, delta_time);
+
+        //The dead zone is given in screen units, so it covers the same part of the screen at every zoom
+        let dead_zone_x = config.camera_dead_zone_x / self.zoom;
+        let dead_zone_y = config.camera_dead_zone_y / self.zoom;
+        let desired = cgmath::Vector2 {
+            x: Camera::follow(self.position.x, target.x, dead_zone_x),
+            y: Camera::follow(self.position.y, target.y, dead_zone_y),
+        };
+
+        let blend = if config.camera_smoothing > 0.0 { 1.0 - (-delta_time / config.camera_smoothing).exp() } else { 1.0 };
+        self.position = self.clamp_to_bounds(self.position + (desired - self.position) * blend);
+    }
+
+    //Closest position that has the target inside the dead zone
+    fn follow(position: f32, target: f32, dead_zone: f32) -> f32
+    {
+        if target > position + dead_zone
+        {
+            target - dead_zone
+        }
+        else if target < position - dead_zone
+        {
+            target + dead_zone
+        }
+        else
+        {
+            position
+        }
+    }
+
+    fn clamp_to_bounds(&self, position: cgmath::Vector2<f32>) -> cgmath::Vector2<f32>
+    {
+        let half_extent = 1.0 / self.zoom;
+        cgmath::Vector2 {
+            x: Camera::clamp_axis(position.x, self.bounds_min.x, self.bounds_max.x, half_extent),
+            y: Camera::clamp_axis(position.y, self.bounds_min.y, self.bounds_max.y, half_extent),
+        }
+    }
+
+    //Bounds smaller than the screen are centered
+    fn clamp_axis(position: f32, min: f32, max: f32, half_extent: f32) -> f32
+    {
+        if max - min <= 2.0 * half_extent
+        {
+            return (min + max) / 2.0;
+        }
+        position.clamp(min + half_extent, max - half_extent)
+    }
+
+    pub fn to_view_model(&self, config: &GameConfig) -> CameraViewModel
+    {
+        if !self.shake_enabled
+        {
+            return CameraViewModel { position: self.position, zoom: self.zoom, rotation: 0.0 };
+        }
+
+        //The offset is in screen units, so it looks the same at every zoom
+        let (offset, rotation) = self.shake.get_shake(config);
+        CameraViewModel { position: self.position + offset / self.zoom, zoom: self.zoom, rotation }
+    }
+}
+
+impl Default for Camera
+{
+    fn default() -> Camera
+    {
+        Camera::new()
+    }
+}
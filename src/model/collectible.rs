@@ -0,0 +1,54 @@
+use crate::model::model_utils::{GridPosition, grid_to_position};
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum CollectibleKind
+{
+    Berry,
+    Egg,
+}
+
+impl CollectibleKind
+{
+    pub fn get_points(&self) -> u32
+    {
+        match self
+        {
+            CollectibleKind::Berry => 10,
+            CollectibleKind::Egg => 25,
+        }
+    }
+
+    pub fn get_sprite(&self) -> &'static str
+    {
+        match self
+        {
+            CollectibleKind::Berry => "berry",
+            CollectibleKind::Egg => "egg",
+        }
+    }
+}
+
+pub struct Collectible<T>
+{
+    pub pos: T,
+    pub kind: CollectibleKind,
+    pub collected: bool,
+}
+
+impl<T> Collectible<T>
+{
+    pub fn new(pos: T, kind: CollectibleKind) -> Collectible<T>
+    {
+        Collectible { pos, kind, collected: false }
+    }
+}
+
+impl Collectible<GridPosition>
+{
+    pub fn from(grid_collectible: &Collectible<GridPosition>, width: f32, height: f32) -> Collectible<cgmath::Vector2<f32>>
+    {
+        let pos = grid_to_position(&grid_collectible.pos, width, height);
+
+        Collectible::new(pos, grid_collectible.kind)
+    }
+}
@@ -1,13 +1,53 @@
 use cgmath;
-use crate::model::model_utils::SpriteAnimationMetaData;
+use cgmath::InnerSpace;
+use crate::model::animation_controller::{AnimationController, Animation, AnimationCondition, PlayMode};
 use crate::model::animation_trait::SpriteAnimation;
+use crate::model::health::Health;
+use crate::sprite_atlas::SpriteAtlas;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Direction
+{
+    Left,
+    Right,
+    Down,
+    Up,
+}
+
+impl Direction
+{
+    pub fn get_move_animation(&self) -> &'static str
+    {
+        match self
+        {
+            Direction::Left => Fox::MOVE_LEFT,
+            Direction::Right => Fox::MOVE_RIGHT,
+            Direction::Down => Fox::MOVE_DOWN,
+            Direction::Up => Fox::MOVE_UP,
+        }
+    }
+
+    pub fn get_idle_animation(&self) -> &'static str
+    {
+        match self
+        {
+            Direction::Left => Fox::IDLE_LEFT,
+            Direction::Right => Fox::IDLE_RIGHT,
+            Direction::Down => Fox::IDLE_DOWN,
+            Direction::Up => Fox::IDLE_UP,
+        }
+    }
+}
 
 pub struct Fox
 {
     pub pos: cgmath::Vector2<f32>,
-    sprite_animations: std::collections::HashMap<&'static str, SpriteAnimationMetaData>,
-    current_animation: &'static str,
-    animation_time: f32,
+    pub velocity: cgmath::Vector2<f32>,
+    //Direction the fox looked at last, kept while standing still
+    pub facing: Direction,
+    pub health: Health,
+    animation_controller: AnimationController,
+    frame_time: f32,
 }
 
 impl Fox
@@ -16,44 +56,109 @@ impl Fox
     pub const MOVE_RIGHT: &'static str = "MoveRight";
     pub const MOVE_DOWN: &'static str = "MoveDown";
     pub const MOVE_UP: &'static str = "MoveUp";
+    pub const IDLE_LEFT: &'static str = "IdleLeft";
+    pub const IDLE_RIGHT: &'static str = "IdleRight";
+    pub const IDLE_DOWN: &'static str = "IdleDown";
+    pub const IDLE_UP: &'static str = "IdleUp";
+    pub const DIVE: &'static str = "Dive";
+
+    pub const FOOTSTEP_EVENT: &'static str = "footstep";
+    pub const DIVE_EVENT: &'static str = "dive";
+    pub const DIVE_TRIGGER: &'static str = "Dive";
 
-    pub fn new(pos: cgmath::Vector2<f32>) -> Fox
+    pub fn new(pos: cgmath::Vector2<f32>, hit_points: i32, frame_time: f32, atlas: &SpriteAtlas) -> Result<Fox, String>
+    {
+        Ok(Fox { 
+            pos, 
+            velocity: cgmath::Vector2 { x: 0.0, y: 0.0 }, 
+            facing: Direction::Down, 
+            health: Health::new(hit_points), 
+            animation_controller: Fox::create_animation_controller(frame_time, atlas)?,
+            frame_time,
+        })
+    }
+
+    fn create_animation_controller(frame_time: f32, atlas: &SpriteAtlas) -> Result<AnimationController, String>
     {
         let mut animations = std::collections::HashMap::new();
-        animations.insert(Fox::MOVE_LEFT, SpriteAnimationMetaData{ from_index: 3, to_index: 6, timeout: 0.1 });
-        animations.insert(Fox::MOVE_RIGHT, SpriteAnimationMetaData{ from_index: 6, to_index: 9, timeout: 0.1 });
-        animations.insert(Fox::MOVE_DOWN, SpriteAnimationMetaData{ from_index: 0, to_index: 3, timeout: 0.1 });
-        animations.insert(Fox::MOVE_UP, SpriteAnimationMetaData{ from_index: 9, to_index: 12, timeout: 0.1 });
+        animations.insert(Fox::MOVE_LEFT, Animation::from_atlas(atlas, "fox_move_left", frame_time, PlayMode::Loop)?.with_event(1, Fox::FOOTSTEP_EVENT));
+        animations.insert(Fox::MOVE_RIGHT, Animation::from_atlas(atlas, "fox_move_right", frame_time, PlayMode::Loop)?.with_event(1, Fox::FOOTSTEP_EVENT));
+        animations.insert(Fox::MOVE_DOWN, Animation::from_atlas(atlas, "fox_move_down", frame_time, PlayMode::Loop)?.with_event(1, Fox::FOOTSTEP_EVENT));
+        animations.insert(Fox::MOVE_UP, Animation::from_atlas(atlas, "fox_move_up", frame_time, PlayMode::Loop)?.with_event(1, Fox::FOOTSTEP_EVENT));
+        animations.insert(Fox::IDLE_LEFT, Animation::from_atlas(atlas, "fox_idle_left", 1.0, PlayMode::Loop)?);
+        animations.insert(Fox::IDLE_RIGHT, Animation::from_atlas(atlas, "fox_idle_right", 1.0, PlayMode::Loop)?);
+        animations.insert(Fox::IDLE_DOWN, Animation::from_atlas(atlas, "fox_idle_down", 1.0, PlayMode::Loop)?);
+        animations.insert(Fox::IDLE_UP, Animation::from_atlas(atlas, "fox_idle_up", 1.0, PlayMode::Loop)?);
+        animations.insert(Fox::DIVE, Animation::from_atlas(atlas, "fox_dive", frame_time / 2.0, PlayMode::OneShot)?.with_event(2, Fox::DIVE_EVENT));
 
-        Fox { pos: pos, sprite_animations: animations, current_animation: Fox::MOVE_LEFT, animation_time: 0.0 }
+        let mut controller = AnimationController::new(animations, Fox::IDLE_DOWN)?;
+        for animation_name in [Fox::MOVE_LEFT, Fox::MOVE_RIGHT, Fox::MOVE_DOWN, Fox::MOVE_UP, Fox::IDLE_LEFT, Fox::IDLE_RIGHT, Fox::IDLE_DOWN, Fox::IDLE_UP].iter()
+        {
+            controller.add_transition(animation_name, Fox::DIVE, AnimationCondition::Trigger(Fox::DIVE_TRIGGER))?;
+        }
+        controller.add_transition(Fox::DIVE, Fox::IDLE_DOWN, AnimationCondition::Finished)?;
+        Ok(controller)
     }
-}
 
+    //Moves the fox along the given input direction, which does not need to be normalized. A zero direction lets the fox idle.
+    pub fn walk(&mut self, direction: cgmath::Vector2<f32>, speed: f32, delta_time: f32) -> Result<(), String>
+    {
+        if direction.magnitude2() == 0.0
+        {
+            self.velocity = cgmath::Vector2 { x: 0.0, y: 0.0 };
+            return self.update_animation(self.facing.get_idle_animation(), delta_time);
+        }
 
-impl SpriteAnimation for Fox
-{
-    fn get_sprite_animations(&self) -> &std::collections::HashMap<&'static str, SpriteAnimationMetaData>
+        self.velocity = direction.normalize() * speed;
+        self.pos += self.velocity * delta_time;
+        self.facing = Fox::get_facing(self.facing, direction);
+        self.update_animation(self.facing.get_move_animation(), delta_time)
+    }
+
+    //When walking diagonally the fox keeps looking the way it did, if that is one of the two directions
+    fn get_facing(current_facing: Direction, direction: cgmath::Vector2<f32>) -> Direction
     {
-        &self.sprite_animations
+        let horizontal = if direction.x < 0.0 { Some(Direction::Left) } else if direction.x > 0.0 { Some(Direction::Right) } else { None };
+        let vertical = if direction.y < 0.0 { Some(Direction::Down) } else if direction.y > 0.0 { Some(Direction::Up) } else { None };
+
+        if Some(current_facing) == horizontal || Some(current_facing) == vertical
+        {
+            return current_facing;
+        }
+
+        horizontal.or(vertical).unwrap_or(current_facing)
     }
 
-    fn get_current_animation(&self) -> &'static str
+    pub fn dive(&mut self)
     {
-        self.current_animation
+        self.animation_controller.trigger(Fox::DIVE_TRIGGER);
     }
 
-    fn set_current_animation(&mut self, new_current_animation: &'static str)
+    //Starts over with the animations of the new atlas
+    pub fn set_sprite_atlas(&mut self, atlas: &SpriteAtlas) -> Result<(), String>
     {
-        self.current_animation = new_current_animation;
+        self.animation_controller = Fox::create_animation_controller(self.frame_time, atlas)?;
+        Ok(())
     }
 
-    fn get_animation_time(&self) -> f32
+    pub fn set_frame_time(&mut self, frame_time: f32)
     {
-        self.animation_time
+        //The idle animations keep their own pace
+        self.animation_controller.scale_durations(&[Fox::MOVE_LEFT, Fox::MOVE_RIGHT, Fox::MOVE_DOWN, Fox::MOVE_UP, Fox::DIVE], frame_time / self.frame_time);
+        self.frame_time = frame_time;
     }
+}
 
-    fn set_animation_time(&mut self, new_animation_time: f32)
+
+impl SpriteAnimation for Fox
+{
+    fn get_animation_controller(&self) -> &AnimationController
     {
-        self.animation_time = new_animation_time;
+        &self.animation_controller
     }
-}
\ No newline at end of file
+
+    fn get_animation_controller_mut(&mut self) -> &mut AnimationController
+    {
+        &mut self.animation_controller
+    }
+}
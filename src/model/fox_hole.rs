@@ -6,16 +6,17 @@ pub struct FoxHole<T>
     pub entry: T,
     pub exit: T,
     pub used: bool,
-    pub entry_sprite: i32,
-    pub exit_sprite: i32,
-    pub closed_sprite: i32,
+    pub time_since_used: f32,
+    pub entry_sprite: &'static str,
+    pub exit_sprite: &'static str,
+    pub closed_sprite: &'static str,
 }
 
 impl<T> FoxHole<T>
 {
     pub fn new(entry: T, exit: T, used: Option<bool>) -> FoxHole<T>
     {
-        FoxHole { entry: entry, exit: exit, used: used.unwrap_or(false), entry_sprite: 12, exit_sprite: 12, closed_sprite: 13, }
+        FoxHole { entry, exit, used: used.unwrap_or(false), time_since_used: 0.0, entry_sprite: "hole", exit_sprite: "hole", closed_sprite: "hole_closed", }
     }
 }
 
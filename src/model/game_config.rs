@@ -0,0 +1,309 @@
+#[derive(Copy, Clone, PartialEq)]
+pub enum Difficulty
+{
+    Easy = 0,
+    Normal = 1,
+    Hard = 2,
+}
+
+impl Difficulty
+{
+    pub fn from(difficulty_code: u8) -> Result<Difficulty, String>
+    {
+        match difficulty_code
+        {
+            0 => Ok(Difficulty::Easy),
+            1 => Ok(Difficulty::Normal),
+            2 => Ok(Difficulty::Hard),
+            _ => Err(format!("Difficulty {} not found", difficulty_code)),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Difficulty, String>
+    {
+        match name
+        {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Difficulty {} not found", name)),
+        }
+    }
+}
+
+//All gameplay tuning values of the model in one place
+#[derive(Clone)]
+pub struct GameConfig
+{
+    pub fox_speed: f32,
+    pub fox_animation_frame_time: f32,
+    pub sprite_size: f32,
+    pub closed_hole_size: f32,
+    pub collectible_size: f32,
+    pub ghost_alpha: f32,
+
+    pub hole_use_radius: f32,
+    //Multiplier for the respawn time of fox holes, that a level defines
+    pub hole_cooldown_factor: f32,
+    pub hole_particles_max_speed: f32,
+    pub hole_particles_lifetime: f32,
+    pub hole_vignette_duration: f32,
+
+    pub wolf_bite_radius: f32,
+    pub wolf_speed: f32,
+    pub wolf_detection_range: f32,
+    //Seconds a wolf needs to start the hunt after it spotted the fox
+    pub wolf_reaction_time: f32,
+    //Wolves placed in a level hunt the fox once it came within the detection range for the reaction time. Without it they
+    //stand still, the ones of endless waves always hunt.
+    pub placed_wolves_hunt: bool,
+
+    pub max_hit_points: i32,
+    pub invulnerability_time: f32,
+    pub knockback_speed: f32,
+    pub knockback_time: f32,
+    pub lives: u32,
+
+    pub collect_radius: f32,
+    pub survival_points_per_second: u32,
+    pub wave_bonus: u32,
+    pub speed_bonus_per_second: f32,
+    pub no_holes_bonus: u32,
+
+    //1 shows the whole level, 2 half of it in both directions
+    pub camera_zoom: f32,
+    //Half size of the area around the center of the screen the fox moves in without the camera following, in screen units from 0 to 1
+    pub camera_dead_zone_x: f32,
+    pub camera_dead_zone_y: f32,
+    //Seconds the camera needs to catch up about two thirds of the distance, 0 follows immediately
+    pub camera_smoothing: f32,
+
+    //Trauma from 0 to 1 that events add to the screen shake
+    pub bite_trauma: f32,
+    pub dive_trauma: f32,
+    //Trauma lost per second
+    pub screen_shake_decay: f32,
+    //Shake at full trauma, the offset in screen units and the rotation in radians
+    pub screen_shake_max_offset: f32,
+    pub screen_shake_max_rotation: f32,
+    //How often the shake changes direction per second
+    pub screen_shake_frequency: f32,
+}
+
+impl GameConfig
+{
+    //Closer than this the fox would hardly see anything around it
+    const MAX_CAMERA_ZOOM: f32 = 8.0;
+
+    pub fn from_difficulty(difficulty: Difficulty) -> GameConfig
+    {
+        let normal = GameConfig::normal();
+
+        match difficulty
+        {
+            Difficulty::Easy => GameConfig {
+                fox_speed: 0.25,
+                hole_cooldown_factor: 0.6,
+                wolf_speed: 0.07,
+                wolf_detection_range: 0.5,
+                wolf_reaction_time: 0.8,
+                max_hit_points: 4,
+                lives: 5,
+                ..normal
+            },
+            Difficulty::Normal => normal,
+            Difficulty::Hard => GameConfig {
+                fox_speed: 0.18,
+                hole_cooldown_factor: 1.5,
+                wolf_speed: 0.13,
+                wolf_detection_range: 1.0,
+                wolf_reaction_time: 0.2,
+                max_hit_points: 2,
+                lives: 1,
+                ..normal
+            },
+        }
+    }
+
+    fn normal() -> GameConfig
+    {
+        GameConfig {
+            fox_speed: 0.2,
+            fox_animation_frame_time: 0.1,
+            sprite_size: 0.2,
+            closed_hole_size: 0.15,
+            collectible_size: 0.15,
+            ghost_alpha: 0.4,
+
+            hole_use_radius: 0.1,
+            hole_cooldown_factor: 1.0,
+            hole_particles_max_speed: 0.1,
+            hole_particles_lifetime: 3.0,
+            hole_vignette_duration: 1.5,
+
+            wolf_bite_radius: 0.15,
+            wolf_speed: 0.1,
+            wolf_detection_range: 0.7,
+            wolf_reaction_time: 0.5,
+            placed_wolves_hunt: true,
+
+            max_hit_points: 3,
+            invulnerability_time: 1.0,
+            knockback_speed: 1.0,
+            knockback_time: 0.15,
+            lives: 3,
+
+            collect_radius: 0.1,
+            survival_points_per_second: 10,
+            wave_bonus: 100,
+            speed_bonus_per_second: 5.0,
+            no_holes_bonus: 50,
+
+            camera_zoom: 1.0,
+            camera_dead_zone_x: 0.2,
+            camera_dead_zone_y: 0.2,
+            camera_smoothing: 0.15,
+
+            bite_trauma: 0.6,
+            dive_trauma: 0.3,
+            screen_shake_decay: 1.5,
+            screen_shake_max_offset: 0.05,
+            screen_shake_max_rotation: 0.05,
+            screen_shake_frequency: 15.0,
+        }
+    }
+
+    //Reads lines of "key = value", everything after a # is a comment. Settings that are not listed keep
+    //their value from the base config. A "difficulty = easy|normal|hard" line resets all settings to that preset,
+    //so it should come first. Values out of their range are an error, see check.
+    pub fn parse(text: &str, base: GameConfig) -> Result<GameConfig, String>
+    {
+        let mut config = base;
+
+        for (line_index, line) in text.lines().enumerate()
+        {
+            let line = match line.find('#')
+            {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            }.trim();
+
+            if line.is_empty()
+            {
+                continue;
+            }
+
+            let mut key_value = line.splitn(2, '=');
+            let key = key_value.next().unwrap_or("").trim();
+            let value = key_value.next().ok_or(format!("Line {}: expected key = value", line_index + 1))?.trim();
+
+            config.set_value(key, value).map_err(|err| format!("Line {}: {}", line_index + 1, err))?;
+        }
+
+        config.check()?;
+        Ok(config)
+    }
+
+    //Durations the model divides by have to be positive, other times can be 0 to turn something off
+    fn check(&self) -> Result<(), String>
+    {
+        for (key, value) in [ ("fox_animation_frame_time", self.fox_animation_frame_time), ("hole_particles_lifetime", self.hole_particles_lifetime), ("hole_vignette_duration", self.hole_vignette_duration),
+            ("sprite_size", self.sprite_size), ("closed_hole_size", self.closed_hole_size), ("collectible_size", self.collectible_size),
+            ("hole_use_radius", self.hole_use_radius), ("wolf_bite_radius", self.wolf_bite_radius), ("collect_radius", self.collect_radius) ].iter()
+        {
+            if value.is_nan() || *value <= 0.0
+            {
+                return Err(format!("{} has to be positive", key));
+            }
+        }
+
+        for (key, value) in [ ("invulnerability_time", self.invulnerability_time), ("knockback_time", self.knockback_time), ("wolf_reaction_time", self.wolf_reaction_time), ("camera_smoothing", self.camera_smoothing),
+            ("wolf_detection_range", self.wolf_detection_range), ("screen_shake_decay", self.screen_shake_decay) ].iter()
+        {
+            if value.is_nan() || *value < 0.0
+            {
+                return Err(format!("{} can not be negative", key));
+            }
+        }
+
+        for (key, value) in [ ("bite_trauma", self.bite_trauma), ("dive_trauma", self.dive_trauma), ("camera_dead_zone_x", self.camera_dead_zone_x), ("camera_dead_zone_y", self.camera_dead_zone_y) ].iter()
+        {
+            if !(0.0..=1.0).contains(value)
+            {
+                return Err(format!("{} has to be between 0 and 1", key));
+            }
+        }
+
+        if self.max_hit_points <= 0
+        {
+            return Err("max_hit_points has to be positive".to_string());
+        }
+
+        if !(1.0..=GameConfig::MAX_CAMERA_ZOOM).contains(&self.camera_zoom)
+        {
+            return Err(format!("camera_zoom has to be between 1 and {}", GameConfig::MAX_CAMERA_ZOOM));
+        }
+
+        Ok(())
+    }
+
+    fn set_value(&mut self, key: &str, value: &str) -> Result<(), String>
+    {
+        match key
+        {
+            "difficulty" => *self = GameConfig::from_difficulty(Difficulty::from_name(value)?),
+
+            "fox_speed" => self.fox_speed = parse_value(key, value)?,
+            "fox_animation_frame_time" => self.fox_animation_frame_time = parse_value(key, value)?,
+            "sprite_size" => self.sprite_size = parse_value(key, value)?,
+            "closed_hole_size" => self.closed_hole_size = parse_value(key, value)?,
+            "collectible_size" => self.collectible_size = parse_value(key, value)?,
+            "ghost_alpha" => self.ghost_alpha = parse_value(key, value)?,
+
+            "hole_use_radius" => self.hole_use_radius = parse_value(key, value)?,
+            "hole_cooldown_factor" => self.hole_cooldown_factor = parse_value(key, value)?,
+            "hole_particles_max_speed" => self.hole_particles_max_speed = parse_value(key, value)?,
+            "hole_particles_lifetime" => self.hole_particles_lifetime = parse_value(key, value)?,
+            "hole_vignette_duration" => self.hole_vignette_duration = parse_value(key, value)?,
+
+            "wolf_bite_radius" => self.wolf_bite_radius = parse_value(key, value)?,
+            "wolf_speed" => self.wolf_speed = parse_value(key, value)?,
+            "wolf_detection_range" => self.wolf_detection_range = parse_value(key, value)?,
+            "wolf_reaction_time" => self.wolf_reaction_time = parse_value(key, value)?,
+            "placed_wolves_hunt" => self.placed_wolves_hunt = parse_value(key, value)?,
+
+            "max_hit_points" => self.max_hit_points = parse_value(key, value)?,
+            "invulnerability_time" => self.invulnerability_time = parse_value(key, value)?,
+            "knockback_speed" => self.knockback_speed = parse_value(key, value)?,
+            "knockback_time" => self.knockback_time = parse_value(key, value)?,
+            "lives" => self.lives = parse_value(key, value)?,
+
+            "collect_radius" => self.collect_radius = parse_value(key, value)?,
+            "survival_points_per_second" => self.survival_points_per_second = parse_value(key, value)?,
+            "wave_bonus" => self.wave_bonus = parse_value(key, value)?,
+            "speed_bonus_per_second" => self.speed_bonus_per_second = parse_value(key, value)?,
+            "no_holes_bonus" => self.no_holes_bonus = parse_value(key, value)?,
+
+            "camera_zoom" => self.camera_zoom = parse_value(key, value)?,
+            "camera_dead_zone_x" => self.camera_dead_zone_x = parse_value(key, value)?,
+            "camera_dead_zone_y" => self.camera_dead_zone_y = parse_value(key, value)?,
+            "camera_smoothing" => self.camera_smoothing = parse_value(key, value)?,
+
+            "bite_trauma" => self.bite_trauma = parse_value(key, value)?,
+            "dive_trauma" => self.dive_trauma = parse_value(key, value)?,
+            "screen_shake_decay" => self.screen_shake_decay = parse_value(key, value)?,
+            "screen_shake_max_offset" => self.screen_shake_max_offset = parse_value(key, value)?,
+            "screen_shake_max_rotation" => self.screen_shake_max_rotation = parse_value(key, value)?,
+            "screen_shake_frequency" => self.screen_shake_frequency = parse_value(key, value)?,
+
+            _ => return Err(format!("Unknown setting {}", key)),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String>
+{
+    value.parse::<T>().map_err(|_| format!("Invalid value {} for {}", value, key))
+}
@@ -0,0 +1,34 @@
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameMode
+{
+    Campaign = 0,
+    Endless = 1,
+}
+
+impl GameMode
+{
+    pub fn from(mode_code: u8) -> Result<GameMode, String>
+    {
+        match mode_code
+        {
+            0 => Ok(GameMode::Campaign),
+            1 => Ok(GameMode::Endless),
+            _ => Err(format!("Game mode {} not found", mode_code)),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            GameMode::Campaign => "campaign",
+            GameMode::Endless => "endless",
+        }
+    }
+
+    //Identifies a level, e.g. for personal bests and the level sections of the asset manifest
+    pub fn get_level_key(&self, level_code: u8) -> String
+    {
+        format!("{}_{}", self.get_name(), level_code)
+    }
+}
@@ -0,0 +1,31 @@
+pub struct Health
+{
+    pub hit_points: i32,
+    pub invulnerable_time_left: f32,
+    pub knockback: cgmath::Vector2<f32>,
+    pub knockback_time_left: f32,
+}
+
+impl Health
+{
+    pub fn new(hit_points: i32) -> Health
+    {
+        Health { hit_points, invulnerable_time_left: 0.0, knockback: cgmath::Vector2 { x: 0.0, y: 0.0 }, knockback_time_left: 0.0 }
+    }
+
+    pub fn is_invulnerable(&self) -> bool
+    {
+        self.invulnerable_time_left > 0.0
+    }
+
+    pub fn is_knocked_back(&self) -> bool
+    {
+        self.knockback_time_left > 0.0
+    }
+
+    pub fn update(&mut self, delta_time: f32)
+    {
+        self.invulnerable_time_left = (self.invulnerable_time_left - delta_time).max(0.0);
+        self.knockback_time_left = (self.knockback_time_left - delta_time).max(0.0);
+    }
+}
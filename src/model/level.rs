@@ -1,13 +1,27 @@
 use crate::model::fox_hole::FoxHole;
 use crate::model::model_utils::GridPosition;
-use crate::model::wolf::Wolf;
+use crate::model::spawner::Spawner;
+use crate::model::wave_schedule::{Wave, WaveSchedule};
+use crate::model::collectible::{Collectible, CollectibleKind};
+use crate::model::rank::RankThresholds;
+use crate::model::light::{Light, Lighting};
 
 pub struct Level
 {
     start_pos: GridPosition,
     data: std::vec::Vec<std::vec::Vec<i32>>,
     fox_holes: std::vec::Vec<FoxHole<GridPosition>>,
-    wolves: std::vec::Vec<Wolf<GridPosition>>,
+    //Wolves placed in the level only need a position, they get their animations once the level is loaded
+    wolves: std::vec::Vec<GridPosition>,
+    spawners: std::vec::Vec<Spawner<GridPosition>>,
+    wave_schedule: Option<WaveSchedule>,
+    hole_respawn_time: Option<f32>,
+    collectibles: std::vec::Vec<Collectible<GridPosition>>,
+    //Time in seconds to beat for a speed bonus
+    par_time: f32,
+    rank_thresholds: RankThresholds,
+    //None for levels in daylight
+    lighting: Option<Lighting<GridPosition>>,
 }
 
 impl Level
@@ -27,10 +41,45 @@ impl Level
         &self.fox_holes
     }
     
-    pub fn get_wolves(&self) -> &std::vec::Vec<Wolf<GridPosition>>
+    pub fn get_wolves(&self) -> &std::vec::Vec<GridPosition>
     {
         &self.wolves
     }
+
+    pub fn get_spawners(&self) -> &std::vec::Vec<Spawner<GridPosition>>
+    {
+        &self.spawners
+    }
+
+    pub fn get_wave_schedule(&self) -> &Option<WaveSchedule>
+    {
+        &self.wave_schedule
+    }
+
+    pub fn get_hole_respawn_time(&self) -> Option<f32>
+    {
+        self.hole_respawn_time
+    }
+
+    pub fn get_collectibles(&self) -> &std::vec::Vec<Collectible<GridPosition>>
+    {
+        &self.collectibles
+    }
+
+    pub fn get_par_time(&self) -> f32
+    {
+        self.par_time
+    }
+
+    pub fn get_rank_thresholds(&self) -> &RankThresholds
+    {
+        &self.rank_thresholds
+    }
+
+    pub fn get_lighting(&self) -> &Option<Lighting<GridPosition>>
+    {
+        &self.lighting
+    }
 }
 
 
@@ -52,7 +101,95 @@ impl Levels
                 vec![ 1, 1, 1, 1, 1, ],
             ],
             fox_holes: vec![ FoxHole::new(GridPosition { column: 1, row: 2 }, GridPosition { column: 2, row: 1 }, None), ],
-            wolves: vec![ Wolf::new(GridPosition { column: 2, row: 4 }) ],
+            wolves: vec![ GridPosition { column: 2, row: 4 } ],
+            spawners: vec![],
+            wave_schedule: None,
+            hole_respawn_time: None,
+            collectibles: vec![
+                Collectible::new(GridPosition { column: 0, row: 0 }, CollectibleKind::Berry),
+                Collectible::new(GridPosition { column: 4, row: 0 }, CollectibleKind::Berry),
+                Collectible::new(GridPosition { column: 4, row: 2 }, CollectibleKind::Egg),
+            ],
+            par_time: 20.0,
+            rank_thresholds: RankThresholds { bronze: 45, silver: 100, gold: 150 },
+            lighting: None,
+        }
+    }
+
+    //At night, the blocks between the paths cast shadows
+    pub fn level_1() -> Level
+    {
+        Level 
+        {
+            start_pos: GridPosition { column: 3, row: 3 },
+            data: vec![ 
+                vec![ 1, 1, 1, 1, 1, 1, 1, ],
+                vec![ 1, 0, 0, 1, 0, 0, 1, ],
+                vec![ 1, 0, 1, 1, 1, 0, 1, ],
+                vec![ 1, 1, 1, 1, 1, 1, 1, ],
+                vec![ 1, 0, 1, 1, 1, 0, 1, ],
+                vec![ 1, 0, 0, 1, 0, 0, 1, ],
+                vec![ 1, 1, 1, 1, 1, 1, 1, ],
+            ],
+            fox_holes: vec![ FoxHole::new(GridPosition { column: 0, row: 0 }, GridPosition { column: 6, row: 6 }, None), ],
+            wolves: vec![ GridPosition { column: 3, row: 0 }, GridPosition { column: 3, row: 6 } ],
+            spawners: vec![],
+            wave_schedule: None,
+            hole_respawn_time: None,
+            collectibles: vec![
+                Collectible::new(GridPosition { column: 0, row: 6 }, CollectibleKind::Berry),
+                Collectible::new(GridPosition { column: 6, row: 0 }, CollectibleKind::Egg),
+            ],
+            par_time: 30.0,
+            rank_thresholds: RankThresholds { bronze: 35, silver: 60, gold: 85 },
+            lighting: Some(Lighting {
+                ambient: cgmath::Vector3 { x: 0.08, y: 0.1, z: 0.2 },
+                fox_radius: 2.5,
+                hole_radius: 1.5,
+                occluding_tiles: vec![ 0 ],
+                lights: vec![
+                    Light::torch(GridPosition { column: 0, row: 3 }),
+                    Light::torch(GridPosition { column: 6, row: 3 }),
+                ],
+            }),
+        }
+    }
+
+    pub fn endless_0() -> Level
+    {
+        Level 
+        {
+            start_pos: GridPosition { column: 2, row: 2 },
+            data: vec![ 
+                vec![ 1, 1, 1, 1, 1, ],
+                vec![ 1, 0, 1, 0, 1, ],
+                vec![ 1, 1, 1, 1, 1, ],
+                vec![ 1, 0, 1, 0, 1, ],
+                vec![ 1, 1, 1, 1, 1, ],
+            ],
+            fox_holes: vec![ FoxHole::new(GridPosition { column: 1, row: 1 }, GridPosition { column: 3, row: 3 }, None), ],
+            wolves: vec![],
+            spawners: vec![ 
+                Spawner::new(GridPosition { column: 0, row: 0 }),
+                Spawner::new(GridPosition { column: 4, row: 4 }),
+                Spawner::new(GridPosition { column: 4, row: 0 }),
+                Spawner::new(GridPosition { column: 0, row: 4 }),
+            ],
+            wave_schedule: Some(WaveSchedule {
+                waves: vec![
+                    Wave { wolf_count: 1, spawn_interval: 1.0, pause_before: 2.0 },
+                    Wave { wolf_count: 2, spawn_interval: 2.0, pause_before: 4.0 },
+                    Wave { wolf_count: 4, spawn_interval: 1.5, pause_before: 4.0 },
+                ],
+                escalation: 1.2,
+                wolf_lifetime: 8.0,
+                max_wolves: 5,
+            }),
+            hole_respawn_time: Some(5.0),
+            collectibles: vec![],
+            par_time: 0.0,
+            rank_thresholds: RankThresholds { bronze: 500, silver: 1000, gold: 2000 },
+            lighting: None,
         }
     }
 }
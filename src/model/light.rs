@@ -0,0 +1,71 @@
+use crate::model::model_utils::{GridPosition, grid_to_position};
+use cgmath;
+
+//A light placed in the level, e.g. a torch. The radius is in tiles, the intensity drops by up to the flicker amount.
+pub struct Light<T>
+{
+    pub pos: T,
+    pub radius: f32,
+    pub color: cgmath::Vector3<f32>,
+    pub intensity: f32,
+    pub flicker: f32,
+}
+
+impl<T> Light<T>
+{
+    pub fn new(pos: T, radius: f32, color: cgmath::Vector3<f32>, intensity: f32, flicker: f32) -> Light<T>
+    {
+        Light { pos, radius, color, intensity, flicker }
+    }
+
+    pub fn torch(pos: T) -> Light<T>
+    {
+        Light::new(pos, 3.0, cgmath::Vector3 { x: 1.0, y: 0.6, z: 0.25 }, 1.0, 0.3)
+    }
+
+    //Intensity at the given time. Every light flickers on its own curve, which only depends on the time, so replays look the same.
+    pub fn get_intensity(&self, index: usize, time: f32) -> f32
+    {
+        let phase = index as f32 * 2.39;
+        let wave = 0.5 + 0.25 * (time * 11.0 + phase).sin() + 0.25 * (time * 17.3 + phase * 1.7).sin();
+        self.intensity * (1.0 - self.flicker * wave)
+    }
+}
+
+impl Light<GridPosition>
+{
+    pub fn from(grid_light: &Light<GridPosition>, width: f32, height: f32) -> Light<cgmath::Vector2<f32>>
+    {
+        let pos = grid_to_position(&grid_light.pos, width, height);
+
+        Light::new(pos, grid_light.radius, grid_light.color, grid_light.intensity, grid_light.flicker)
+    }
+}
+
+//Darkness of a night level. Levels without it are fully lit and skip the lighting pass.
+pub struct Lighting<T>
+{
+    //Light that reaches every tile, black is complete darkness
+    pub ambient: cgmath::Vector3<f32>,
+    //Radius in tiles of the light the fox carries with it, 0 for none
+    pub fox_radius: f32,
+    //Radius in tiles of the glow of fox holes that can be used
+    pub hole_radius: f32,
+    //Tiles of the map that block light and cast shadows
+    pub occluding_tiles: std::vec::Vec<i32>,
+    pub lights: std::vec::Vec<Light<T>>,
+}
+
+impl Lighting<GridPosition>
+{
+    pub fn from(grid_lighting: &Lighting<GridPosition>, width: f32, height: f32) -> Lighting<cgmath::Vector2<f32>>
+    {
+        Lighting {
+            ambient: grid_lighting.ambient,
+            fox_radius: grid_lighting.fox_radius,
+            hole_radius: grid_lighting.hole_radius,
+            occluding_tiles: grid_lighting.occluding_tiles.clone(),
+            lights: grid_lighting.lights.iter().map(|x| Light::from(x, width, height)).collect(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//Things that happened during a model update, which the view and the HUD might want to react to
+#[derive(Copy, Clone, PartialEq)]
+pub enum ModelEvent
+{
+    FoxDamaged { hit_points: i32 },
+    FoxDied { lives_left: u32 },
+    GameOver,
+    //A frame event of the fox animation, e.g. "footstep"
+    AnimationEvent { name: &'static str },
+}
+
+impl ModelEvent
+{
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            ModelEvent::FoxDamaged { .. } => "FoxDamaged",
+            ModelEvent::FoxDied { .. } => "FoxDied",
+            ModelEvent::GameOver => "GameOver",
+            ModelEvent::AnimationEvent { name } => name,
+        }
+    }
+}
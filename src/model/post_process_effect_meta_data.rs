@@ -5,4 +5,5 @@ pub struct PostProcessEffectMetaData
     pub name: view_models::PostProcessEffects,
     pub running_time: f32,
     pub max_running_time: f32,
+    pub parameters: view_models::PostProcessParameters,
 }
\ No newline at end of file
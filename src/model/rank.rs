@@ -0,0 +1,52 @@
+#[derive(Copy, Clone, PartialEq)]
+pub enum Rank
+{
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl Rank
+{
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            Rank::Bronze => "bronze",
+            Rank::Silver => "silver",
+            Rank::Gold => "gold",
+        }
+    }
+}
+
+//Minimum score needed for each rank, a score below bronze gets no rank at all
+#[derive(Clone)]
+pub struct RankThresholds
+{
+    pub bronze: u32,
+    pub silver: u32,
+    pub gold: u32,
+}
+
+impl RankThresholds
+{
+    pub fn get_rank(&self, score: u32) -> Option<Rank>
+    {
+        if score >= self.gold
+        {
+            Some(Rank::Gold)
+        }
+        else if score >= self.silver
+        {
+            Some(Rank::Silver)
+        }
+        else if score >= self.bronze
+        {
+            Some(Rank::Bronze)
+        }
+        else
+        {
+            None
+        }
+    }
+}
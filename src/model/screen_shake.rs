@@ -0,0 +1,71 @@
+use crate::model::game_config::GameConfig;
+
+use cgmath;
+
+//Trauma based screen shake: events add trauma between 0 and 1, which decays over time. The shake grows with the square of the trauma,
+//so small hits barely move the screen while big ones add up. The movement is smooth noise of a seeded hash, so the same seed
+//and the same events always shake the same way, e.g. in replays.
+pub struct ScreenShake
+{
+    seed: u32,
+    trauma: f32,
+    time: f32,
+}
+
+impl ScreenShake
+{
+    pub fn new(seed: u32) -> ScreenShake
+    {
+        ScreenShake { seed, trauma: 0.0, time: 0.0 }
+    }
+
+    pub fn add_trauma(&mut self, amount: f32)
+    {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn get_trauma(&self) -> f32
+    {
+        self.trauma
+    }
+
+    pub fn update(&mut self, config: &GameConfig, delta_time: f32)
+    {
+        self.time += delta_time;
+        self.trauma = (self.trauma - config.screen_shake_decay * delta_time).max(0.0);
+    }
+
+    //Offset in screen units and rotation in radians
+    pub fn get_shake(&self, config: &GameConfig) -> (cgmath::Vector2<f32>, f32)
+    {
+        let shake = self.trauma * self.trauma;
+        let t = self.time * config.screen_shake_frequency;
+
+        let offset = cgmath::Vector2 {
+            x: config.screen_shake_max_offset * shake * self.noise(0, t),
+            y: config.screen_shake_max_offset * shake * self.noise(1, t),
+        };
+        (offset, config.screen_shake_max_rotation * shake * self.noise(2, t))
+    }
+
+    //Value noise from -1 to 1, every channel is an independent curve
+    fn noise(&self, channel: u32, t: f32) -> f32
+    {
+        let start = t.floor();
+        let fraction = t - start;
+        let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+
+        let a = self.hash(channel, start as i64);
+        let b = self.hash(channel, start as i64 + 1);
+        a + (b - a) * smooth
+    }
+
+    fn hash(&self, channel: u32, index: i64) -> f32
+    {
+        let mut x = (index as u64) ^ ((self.seed as u64) << 32) ^ ((channel as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        (x >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
@@ -0,0 +1,24 @@
+use crate::model::model_utils::{GridPosition, grid_to_position};
+
+pub struct Spawner<T>
+{
+    pub pos: T,
+}
+
+impl<T> Spawner<T>
+{
+    pub fn new(pos: T) -> Spawner<T>
+    {
+        Spawner { pos }
+    }
+}
+
+impl Spawner<GridPosition>
+{
+    pub fn from(grid_spawner: &Spawner<GridPosition>, width: f32, height: f32) -> Spawner<cgmath::Vector2<f32>>
+    {
+        let pos = grid_to_position(&grid_spawner.pos, width, height);
+
+        Spawner::new(pos)
+    }
+}
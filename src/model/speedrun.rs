@@ -0,0 +1,68 @@
+//Run times are counted in simulation ticks, so they do not depend on the frame rate of the browser
+pub struct SpeedrunTimer
+{
+    pub ticks: u64,
+    pub level_start_tick: u64,
+    //One entry for every level completed in the run, counted from the start of that level like the time of a personal best
+    pub splits: std::vec::Vec<u64>,
+    //Player position of every tick since the level was started, used as ghost for later runs. It stops growing at
+    //MAX_RECORDING_LENGTH, the ghost then stays on its last position.
+    pub recording: std::vec::Vec<cgmath::Vector2<f32>>,
+}
+
+impl SpeedrunTimer
+{
+    //Ten minutes of ticks
+    pub const MAX_RECORDING_LENGTH: usize = 60 * 60 * 10;
+
+    pub fn new() -> SpeedrunTimer
+    {
+        SpeedrunTimer { ticks: 0, level_start_tick: 0, splits: std::vec::Vec::new(), recording: std::vec::Vec::new() }
+    }
+
+    //Counts the tick that is being simulated, so a split during it includes it
+    pub fn tick(&mut self)
+    {
+        self.ticks += 1;
+    }
+
+    pub fn record(&mut self, player_pos: cgmath::Vector2<f32>)
+    {
+        if self.recording.len() < SpeedrunTimer::MAX_RECORDING_LENGTH
+        {
+            self.recording.push(player_pos);
+        }
+    }
+
+    pub fn split(&mut self)
+    {
+        self.splits.push(self.get_level_ticks());
+    }
+
+    pub fn start_level(&mut self)
+    {
+        self.level_start_tick = self.ticks;
+        self.recording.clear();
+    }
+
+    pub fn get_level_ticks(&self) -> u64
+    {
+        self.ticks - self.level_start_tick
+    }
+}
+
+#[derive(Clone)]
+pub struct PersonalBest
+{
+    pub ticks: u64,
+    pub splits: std::vec::Vec<u64>,
+    pub ghost: std::vec::Vec<cgmath::Vector2<f32>>,
+}
+
+impl Default for SpeedrunTimer
+{
+    fn default() -> SpeedrunTimer
+    {
+        SpeedrunTimer::new()
+    }
+}
@@ -0,0 +1,84 @@
+#[derive(Clone)]
+pub struct Wave
+{
+    pub wolf_count: u32,
+    pub spawn_interval: f32,
+    pub pause_before: f32,
+}
+
+//Describes the waves of an endless level. Once the listed waves are used up, the last one is repeated
+//and gets harder by the escalation factor every time.
+#[derive(Clone)]
+pub struct WaveSchedule
+{
+    pub waves: std::vec::Vec<Wave>,
+    pub escalation: f32,
+    pub wolf_lifetime: f32,
+    //Upper bound for wolves alive at the same time. The escalation keeps growing the waves and shortening the spawn interval,
+    //so without it the wolves chasing the fox would pile up without limit.
+    pub max_wolves: usize,
+}
+
+impl WaveSchedule
+{
+    pub fn get_wave(&self, wave_index: usize) -> Wave
+    {
+        let last_index = self.waves.len() - 1;
+        if wave_index <= last_index
+        {
+            return self.waves[wave_index].clone();
+        }
+
+        let last_wave = &self.waves[last_index];
+        let factor = self.escalation.powi((wave_index - last_index) as i32);
+
+        Wave {
+            wolf_count: (last_wave.wolf_count as f32 * factor).ceil() as u32,
+            spawn_interval: last_wave.spawn_interval / factor,
+            pause_before: last_wave.pause_before,
+        }
+    }
+}
+
+pub struct WaveProgress
+{
+    pub wave_index: usize,
+    pub spawned_in_wave: u32,
+    pub timer: f32,
+    pub waves_completed: u32,
+    pub total_spawned: u32,
+}
+
+impl WaveProgress
+{
+    pub fn new() -> WaveProgress
+    {
+        WaveProgress { wave_index: 0, spawned_in_wave: 0, timer: 0.0, waves_completed: 0, total_spawned: 0 }
+    }
+
+    //Returns true, if a wolf should be spawned this frame
+    pub fn update(&mut self, schedule: &WaveSchedule, delta_time: f32, alive_wolves: usize) -> bool
+    {
+        let wave = schedule.get_wave(self.wave_index);
+        self.timer += delta_time;
+
+        if self.spawned_in_wave >= wave.wolf_count
+        {
+            self.wave_index += 1;
+            self.spawned_in_wave = 0;
+            self.timer = 0.0;
+            self.waves_completed += 1;
+            return false;
+        }
+
+        let next_spawn_time = wave.pause_before + self.spawned_in_wave as f32 * wave.spawn_interval;
+        if self.timer >= next_spawn_time && alive_wolves < schedule.max_wolves
+        {
+            self.spawned_in_wave += 1;
+            self.total_spawned += 1;
+            return true;
+        }
+
+        false
+    }
+}
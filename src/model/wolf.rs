@@ -1,26 +1,128 @@
 use crate::model::model_utils::{GridPosition, grid_to_position};
+use crate::model::animation_controller::{AnimationController, Animation, AnimationCondition, PlayMode};
+use crate::model::animation_trait::SpriteAnimation;
+use crate::sprite_atlas::SpriteAtlas;
+use cgmath::InnerSpace;
 
-pub struct Wolf<T>
+pub struct Wolf
 {
-    pub pos: T,
-    pub sprite: i32
+    pub pos: cgmath::Vector2<f32>,
+    animation_controller: AnimationController,
+    pub hunting: bool,
+    //Wolves of an endless wave come for the fox right away
+    pub spawned: bool,
+    //Seconds the fox has been in sight, the wolf starts hunting once it is above the reaction time
+    pub alert_time: f32,
+    pub time_alive: f32,
+    //Wolves with a lifetime give up the hunt and vanish once it has run out
+    pub lifetime: Option<f32>,
 }
 
 
-impl<T> Wolf<T>
+impl Wolf
 {
-    pub fn new(pos: T) -> Wolf<T>
+    pub const IDLE: &'static str = "Idle";
+    pub const HUNT: &'static str = "Hunt";
+    const HUNTING_PARAMETER: &'static str = "Hunting";
+
+    pub fn new(pos: cgmath::Vector2<f32>, atlas: &SpriteAtlas) -> Result<Wolf, String>
+    {
+        Ok(Wolf{ pos, animation_controller: Wolf::create_animation_controller(atlas)?, hunting: false, spawned: false, alert_time: 0.0, time_alive: 0.0, lifetime: None })
+    }
+
+    pub fn hunter(pos: cgmath::Vector2<f32>, lifetime: f32, atlas: &SpriteAtlas) -> Result<Wolf, String>
+    {
+        Ok(Wolf{ pos, animation_controller: Wolf::create_animation_controller(atlas)?, hunting: true, spawned: true, alert_time: 0.0, time_alive: 0.0, lifetime: Some(lifetime) })
+    }
+
+    //Wolf placed in a level at that grid position
+    pub fn from(grid_pos: &GridPosition, width: f32, height: f32, atlas: &SpriteAtlas) -> Result<Wolf, String>
+    {
+        Wolf::new(grid_to_position(grid_pos, width, height), atlas)
+    }
+
+    fn create_animation_controller(atlas: &SpriteAtlas) -> Result<AnimationController, String>
+    {
+        let mut animations = std::collections::HashMap::new();
+        animations.insert(Wolf::IDLE, Animation::from_atlas(atlas, "wolf_idle", 1.0, PlayMode::Loop)?);
+        animations.insert(Wolf::HUNT, Animation::from_atlas(atlas, "wolf_hunt", 0.1, PlayMode::Loop)?);
+
+        let mut controller = AnimationController::new(animations, Wolf::IDLE)?;
+        controller.add_transition(Wolf::IDLE, Wolf::HUNT, AnimationCondition::Parameter(Wolf::HUNTING_PARAMETER, true))?;
+        controller.add_transition(Wolf::HUNT, Wolf::IDLE, AnimationCondition::Parameter(Wolf::HUNTING_PARAMETER, false))?;
+        Ok(controller)
+    }
+
+    pub fn set_sprite_atlas(&mut self, atlas: &SpriteAtlas) -> Result<(), String>
+    {
+        self.animation_controller = Wolf::create_animation_controller(atlas)?;
+        Ok(())
+    }
+
+    pub fn watch(&mut self, target: cgmath::Vector2<f32>, detection_range: f32, reaction_time: f32, delta_time: f32)
     {
-        Wolf{ pos: pos, sprite: 14 }
+        if self.spawned
+        {
+            return;
+        }
+
+        if (target - self.pos).magnitude() < detection_range
+        {
+            self.alert_time += delta_time;
+            self.hunting = self.alert_time >= reaction_time;
+        }
+        else
+        {
+            self.alert_time = 0.0;
+            self.hunting = false;
+        }
+    }
+
+    pub fn hunt(&mut self, target: cgmath::Vector2<f32>, speed: f32, delta_time: f32)
+    {
+        self.time_alive += delta_time;
+
+        let hunting = self.hunting;
+        let controller = self.get_animation_controller_mut();
+        controller.set_parameter(Wolf::HUNTING_PARAMETER, hunting);
+        controller.update(delta_time);
+
+        if !self.hunting
+        {
+            return;
+        }
+
+        let to_target = target - self.pos;
+        let step = speed * delta_time;
+        if to_target.magnitude() <= step
+        {
+            self.pos = target;
+        }
+        else
+        {
+            self.pos += to_target.normalize() * step;
+        }
+    }
+
+    pub fn is_expired(&self) -> bool
+    {
+        match self.lifetime
+        {
+            Some(lifetime) => self.time_alive >= lifetime,
+            None => false,
+        }
     }
 }
 
-impl Wolf<GridPosition>
+impl SpriteAnimation for Wolf
 {
-    pub fn from(grid_hole: &Wolf<GridPosition>, width: f32, height: f32) -> Wolf<cgmath::Vector2<f32>>
+    fn get_animation_controller(&self) -> &AnimationController
     {
-        let pos = grid_to_position(&grid_hole.pos, width, height);
+        &self.animation_controller
+    }
 
-        Wolf::new(pos)
+    fn get_animation_controller_mut(&mut self) -> &mut AnimationController
+    {
+        &mut self.animation_controller
     }
-}
\ No newline at end of file
+}
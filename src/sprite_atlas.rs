@@ -0,0 +1,195 @@
+//Describes where the named sprites are located in a sprite tile map and which of them make up an animation.
+//
+//The descriptor is a text file with one entry per line, everything after a # is a comment:
+//  size <width> <height>                  size of the atlas image in pixels, has to come first
+//  grid <columns> <rows>                  optional, divides the atlas into equally sized cells
+//  frame <name> <x> <y> <width> <height>  rectangle in pixels, measured from the top left corner
+//  cell <name> <column> <row>             cell of the grid, counted from the top left corner
+//  animation <name> <frame> <frame> ...   frames in the order they are played
+//
+//The background tile map is described the same way. It needs a grid, the tiles of the map data are its cells counted row by row.
+
+#[derive(Copy, Clone, PartialEq)]
+pub struct UvRect
+{
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+#[derive(Clone)]
+pub struct SpriteAtlas
+{
+    width: f32,
+    height: f32,
+    grid: Option<(f32, f32)>,
+    frames: std::vec::Vec<UvRect>,
+    //Index into frames by frame name, the view models only carry the index
+    frame_indices: std::collections::HashMap<String, usize>,
+    animations: std::collections::HashMap<String, std::vec::Vec<usize>>,
+}
+
+impl SpriteAtlas
+{
+    pub fn parse(text: &str) -> Result<SpriteAtlas, String>
+    {
+        let mut atlas = SpriteAtlas {
+            width: 0.0,
+            height: 0.0,
+            grid: None,
+            frames: std::vec::Vec::new(),
+            frame_indices: std::collections::HashMap::new(),
+            animations: std::collections::HashMap::new(),
+        };
+        for (line_index, line) in text.lines().enumerate()
+        {
+            let line = match line.find('#')
+            {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+
+            let words: std::vec::Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty()
+            {
+                continue;
+            }
+
+            let error = |msg: &str| format!("Line {}: {}", line_index + 1, msg);
+
+            if words[0] != "size" && atlas.width == 0.0
+            {
+                return Err(error("size has to be the first entry"));
+            }
+
+            match (words[0], words.len())
+            {
+                ("size", 3) =>
+                {
+                    atlas.width = parse_number(words[1]).map_err(|x| error(&x))?;
+                    atlas.height = parse_number(words[2]).map_err(|x| error(&x))?;
+                    if atlas.width <= 0.0 || atlas.height <= 0.0
+                    {
+                        return Err(error("size has to be positive"));
+                    }
+                },
+                ("grid", 3) =>
+                {
+                    let columns = parse_number(words[1]).map_err(|x| error(&x))?;
+                    let rows = parse_number(words[2]).map_err(|x| error(&x))?;
+                    if columns < 1.0 || rows < 1.0 || columns.fract() != 0.0 || rows.fract() != 0.0
+                    {
+                        return Err(error("grid needs a whole number of columns and rows"));
+                    }
+                    atlas.grid = Some((columns, rows));
+                },
+                ("frame", 6) =>
+                {
+                    let x = parse_number(words[2]).map_err(|x| error(&x))?;
+                    let y = parse_number(words[3]).map_err(|x| error(&x))?;
+                    let width = parse_number(words[4]).map_err(|x| error(&x))?;
+                    let height = parse_number(words[5]).map_err(|x| error(&x))?;
+                    let rect = UvRect { min_x: x / atlas.width, min_y: y / atlas.height, max_x: (x + width) / atlas.width, max_y: (y + height) / atlas.height };
+                    atlas.add_frame(words[1], rect).map_err(|x| error(&x))?;
+                },
+                ("cell", 4) =>
+                {
+                    let (columns, rows) = atlas.grid.ok_or_else(|| error("cell needs a grid entry before it"))?;
+                    let column = parse_number(words[2]).map_err(|x| error(&x))?;
+                    let row = parse_number(words[3]).map_err(|x| error(&x))?;
+                    if column < 0.0 || row < 0.0 || column >= columns || row >= rows || column.fract() != 0.0 || row.fract() != 0.0
+                    {
+                        return Err(error(&format!("Cell {} {} is not in the grid of {}x{}", words[2], words[3], columns, rows)));
+                    }
+                    let rect = UvRect { min_x: column / columns, min_y: row / rows, max_x: (column + 1.0) / columns, max_y: (row + 1.0) / rows };
+                    atlas.add_frame(words[1], rect).map_err(|x| error(&x))?;
+                },
+                ("animation", count) if count > 2 =>
+                {
+                    let mut frames = std::vec::Vec::new();
+                    for frame in words[2..].iter()
+                    {
+                        match atlas.frame_indices.get(*frame)
+                        {
+                            Some(index) => frames.push(*index),
+                            None => return Err(error(&format!("Frame {} of animation {} not found", frame, words[1]))),
+                        }
+                    }
+                    atlas.animations.insert(words[1].to_string(), frames);
+                },
+                _ => return Err(error(&format!("Invalid entry {}", line.trim()))),
+            }
+        }
+
+        Ok(atlas)
+    }
+
+    //Frames are stored relative to the size in the descriptor, so a scaled version of the image works as well (e.g. the small sprite tile map).
+    //Anything that is not scaled the same way in both directions would cut the frames at the wrong places, as would cells of the grid that
+    //do not start on a whole pixel.
+    pub fn check_image_size(&self, width: u32, height: u32) -> Result<(), String>
+    {
+        if width == 0 || height == 0 || (width as f32 / height as f32 - self.width / self.height).abs() > 0.001
+        {
+            return Err(format!("Image of {}x{} pixels does not match the {}x{} of the atlas", width, height, self.width, self.height));
+        }
+
+        if let Some((columns, rows)) = self.grid
+        {
+            if !width.is_multiple_of(columns as u32) || !height.is_multiple_of(rows as u32)
+            {
+                return Err(format!("Image of {}x{} pixels can not be divided into the {}x{} grid of the atlas", width, height, columns, rows));
+            }
+        }
+
+        Ok(())
+    }
+
+    //Columns and rows of the grid, if the atlas has one
+    pub fn get_grid(&self) -> Option<(u32, u32)>
+    {
+        self.grid.map(|(columns, rows)| (columns as u32, rows as u32))
+    }
+
+    fn add_frame(&mut self, name: &str, rect: UvRect) -> Result<(), String>
+    {
+        if self.frame_indices.insert(name.to_string(), self.frames.len()).is_some()
+        {
+            return Err(format!("Frame {} is defined twice", name));
+        }
+        self.frames.push(rect);
+        Ok(())
+    }
+
+    pub fn get_frame_index(&self, frame_name: &str) -> Result<usize, String>
+    {
+        self.frame_indices.get(frame_name).cloned().ok_or(format!("Sprite {} not found in atlas", frame_name))
+    }
+
+    pub fn get_uv_rect(&self, frame_index: usize) -> Result<UvRect, String>
+    {
+        self.frames.get(frame_index).cloned().ok_or(format!("Frame {} not found in atlas", frame_index))
+    }
+
+    //Frame indices of the animation. A single frame can be used as animation with only that frame.
+    pub fn get_animation_frames(&self, animation_name: &str) -> Result<std::vec::Vec<usize>, String>
+    {
+        if let Some(frames) = self.animations.get(animation_name)
+        {
+            return Ok(frames.clone());
+        }
+
+        if let Some(index) = self.frame_indices.get(animation_name)
+        {
+            return Ok(vec![ *index ]);
+        }
+
+        Err(format!("Animation {} not found in atlas", animation_name))
+    }
+}
+
+fn parse_number(word: &str) -> Result<f32, String>
+{
+    word.parse::<f32>().map_err(|_| format!("Invalid number {}", word))
+}
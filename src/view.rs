@@ -3,252 +3,671 @@ mod background_helper;
 mod sprites_helper;
 mod particles_helper;
 mod post_process_effect;
-
-use crate::view_models::{SpritesViewModel, LevelViewModel, ParticlesViewModel, PostProcessViewModel, PostProcessEffects};
+mod upscale_helper;
+mod transition_helper;
+mod lighting_helper;
+pub mod render_backend;
+pub mod webgl_backend;
+pub mod recording_backend;
+pub mod software_renderer;
+pub mod scale_mode;
+pub mod texture_filter;
+pub mod transition;
+
+use crate::sprite_atlas::SpriteAtlas;
+use crate::view_models::{SpritesViewModel, LevelViewModel, ParticlesViewModel, LightsViewModel, PostProcessViewModel, CameraViewModel, PostProcessEffect, PostProcessEffects, PostProcessParameters};
+use scale_mode::{ScaleMode, Viewport};
+use texture_filter::TextureFilter;
+use transition::Transition;
+use render_backend::{RenderBackend, ProgramId, TextureId, VertexArrayId, BufferId, FramebufferId, UniformValue};
 
 use image;
 use cgmath;
-use web_sys::{WebGlProgram, WebGl2RenderingContext, WebGlTexture, WebGlVertexArrayObject};
 
 pub struct View 
 {
-    render_texture: WebGlTexture,
-
-    background_shader: WebGlProgram,
-    background_vao: WebGlVertexArrayObject,
+    backend: Box<dyn RenderBackend>,
+
+    canvas_width: i32,
+    canvas_height: i32,
+    scale_mode: ScaleMode,
+    viewport: Viewport,
+    texture_filter: TextureFilter,
+    //Largest size of the scene in pixels, None renders it at the size of the viewport
+    render_resolution: Option<(i32, i32)>,
+
+    //The scene is drawn into this target and then scaled up to the viewport
+    scene_framebuffer: FramebufferId,
+    scene_texture: TextureId,
+    scene_width: i32,
+    scene_height: i32,
+    upscale_shader: ProgramId,
+    //What was scaled up to the canvas last, the scene after the post process effects
+    last_scene_texture: TextureId,
+
+    //Replaces the upscale shader while one scene is replaced by the next
+    transition_shader: ProgramId,
+    transition: Option<Transition>,
+    //Last frame of the old scene
+    snapshot: Option<(FramebufferId, TextureId)>,
+
+    background_shader: ProgramId,
+    background_vao: VertexArrayId,
     background_triangle_count: i32,
-    background_tile_texture: WebGlTexture,
-
-    sprite_shader: WebGlProgram,
-    sprite_texture: WebGlTexture,
+    background_tile_texture: TextureId,
+    background_map_texture: TextureId,
+    map_width: u32,
+    map_height: u32,
+
+    sprite_shader: ProgramId,
+    sprite_vao: VertexArrayId,
+    sprite_instance_buffer: BufferId,
+    sprite_texture: TextureId,
+    sprite_atlas: SpriteAtlas,
     sprite_count: i32,
 
-    particles_shader: WebGlProgram,
+    particles_shader: ProgramId,
+    particles_vao: VertexArrayId,
+    particles_instance_buffer: BufferId,
     particle_systems_count: i32,
 
-    post_process_effect_shaders: std::collections::HashMap<PostProcessEffects, WebGlProgram>,
+    lighting_shader: ProgramId,
+    //Levels in daylight skip the lighting pass
+    lighting_enabled: bool,
+    //Data textures like the map, so there is no limit on the number of lights, see lighting_helper::get_texture_data
+    lights_texture: TextureId,
+    light_rows: u32,
+    occluding_tiles_texture: TextureId,
+    occluding_tile_columns: u32,
+
+    post_process_chain: post_process_effect::chain::PostProcessChain,
+    post_process_effect_shaders: std::collections::HashMap<PostProcessEffects, ProgramId>,
     post_process_effects: std::vec::Vec<Box<dyn post_process_effect::effect::Effect>>,
+    //Effects started by the view itself, e.g. the screen flash when the fox gets bitten
+    view_effects: std::vec::Vec<PostProcessEffect>,
 }
 
 impl View
 {
-    pub fn new(context: &WebGl2RenderingContext, tile_map: image::RgbaImage, sprite_tile_map: image::RgbaImage, width: i32, height: i32) -> Result<View, String>
+    //Resolution of one map tile that the integer scale mode scales in whole multiples of
+    pub const PIXELS_PER_TILE: i32 = 16;
+    const DEFAULT_TEXTURE_FILTER: TextureFilter = TextureFilter::Linear;
+
+    pub fn new(mut backend: Box<dyn RenderBackend>, tile_map: image::RgbaImage, tile_atlas: SpriteAtlas, sprite_tile_map: image::RgbaImage, sprite_atlas: SpriteAtlas, width: i32, height: i32) -> Result<View, String>
     {
-        let background = View::init_background(context, tile_map)?;
-        let sprites = View::init_sprite_renderer(context, sprite_tile_map)?;
-        let particles = View::init_particles_renderer(context)?;
+        let tile_grid = View::get_tile_grid(&tile_map, &tile_atlas)?;
+        sprite_atlas.check_image_size(sprite_tile_map.width(), sprite_tile_map.height())?;
+
+        let background = View::init_background(backend.as_mut(), tile_map, tile_grid)?;
+        let sprites = View::init_sprite_renderer(backend.as_mut(), sprite_tile_map)?;
+        let particles = View::init_particles_renderer(backend.as_mut())?;
+        let lighting_shader = lighting_helper::initialize_shader(backend.as_mut())?;
+        background_helper::update_map_size(backend.as_mut(), lighting_shader, 1, 1)?;
+        let lights_texture = backend.create_data_texture(lighting_helper::LIGHT_VALUES, 1, &[0; lighting_helper::LIGHT_VALUES as usize])?;
+        let occluding_tiles_texture = backend.create_data_texture(1, 1, &[0])?;
         
-        let render_texture = View::init_render_texture(context, width, height)?;
+        let (scene_framebuffer, scene_texture) = backend.create_render_target(width, height, View::DEFAULT_TEXTURE_FILTER.is_linear())?;
+        let upscale_shader = upscale_helper::initialize_shader(backend.as_mut())?;
+        let transition_shader = transition_helper::initialize_shader(backend.as_mut())?;
+        let post_process_chain = post_process_effect::chain::PostProcessChain::new(backend.as_mut(), width, height, View::DEFAULT_TEXTURE_FILTER.is_linear())?;
         
         let mut view = View {
-            render_texture: render_texture,
+            backend,
+
+            canvas_width: width,
+            canvas_height: height,
+            scale_mode: ScaleMode::Letterbox,
+            viewport: Viewport { x: 0, y: 0, width, height },
+            texture_filter: View::DEFAULT_TEXTURE_FILTER,
+            render_resolution: None,
+
+            scene_framebuffer,
+            scene_texture,
+            scene_width: width,
+            scene_height: height,
+            upscale_shader,
+            last_scene_texture: scene_texture,
+
+            transition_shader,
+            transition: None,
+            snapshot: None,
 
             background_shader: background.0,
             background_vao: background.1,
             background_triangle_count: background.2,
             background_tile_texture: background.3,
+            background_map_texture: background.4,
+            map_width: 1,
+            map_height: 1,
 
             sprite_shader: sprites.0,
-            sprite_texture: sprites.1,
+            sprite_vao: sprites.1,
+            sprite_instance_buffer: sprites.2,
+            sprite_texture: sprites.3,
+            sprite_atlas,
             sprite_count: 0,
 
-            particles_shader: particles,
+            particles_shader: particles.0,
+            particles_vao: particles.1,
+            particles_instance_buffer: particles.2,
             particle_systems_count: 0,
 
+            lighting_shader,
+            lighting_enabled: false,
+            lights_texture,
+            light_rows: 1,
+            occluding_tiles_texture,
+            occluding_tile_columns: 1,
+
+            post_process_chain,
             post_process_effect_shaders: std::collections::HashMap::new(),
             post_process_effects: std::vec::Vec::new(),
+            view_effects: std::vec::Vec::new(),
         };
 
-        view.init_post_process_shaders(context)?;
+        view.init_post_process_shaders()?;
+        view.update_viewport()?;
+        view.update_camera(CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 })?;
 
         Ok(view)
     }
 
-    fn init_background(context: &WebGl2RenderingContext, tile_map: image::RgbaImage) -> Result<(WebGlProgram, WebGlVertexArrayObject, i32, WebGlTexture), String>
+    fn init_background(backend: &mut dyn RenderBackend, tile_map: image::RgbaImage, tile_grid: (u32, u32)) -> Result<(ProgramId, VertexArrayId, i32, TextureId, TextureId), String>
     {
-        let program = background_helper::initialize_shader(&context)?;
-        let screen_filling_quad = shader_utils::initialize_quad_with_uvs(&context, &program, cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 2.0, y: 2.0 })?;
-        let tex = shader_utils::initialize_texture(context, tile_map, &program, true)?;  
-        background_helper::set_tile_map_uniforms(context, &program, 2.0, 2.0)?;   
+        let program = background_helper::initialize_shader(backend)?;
+        let screen_filling_quad = backend.create_quad(cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 2.0, y: 2.0 })?;
+        let tex = backend.create_texture(tile_map, View::DEFAULT_TEXTURE_FILTER.is_linear())?;
+        background_helper::set_tile_map_uniforms(backend, program, tile_grid.0 as f32, tile_grid.1 as f32)?;
+
+        //Single tile until the first level is loaded
+        let map_tex = backend.create_data_texture(1, 1, &[0])?;
+        background_helper::update_map_size(backend, program, 1, 1)?;
         
-        Ok((program, screen_filling_quad.1, screen_filling_quad.0 as i32, tex))
+        Ok((program, screen_filling_quad.1, screen_filling_quad.0, tex, map_tex))
+    }
+
+    fn init_sprite_renderer(backend: &mut dyn RenderBackend, texture_image: image::RgbaImage) -> Result<(ProgramId, VertexArrayId, BufferId, TextureId), String>
+    {
+        let program = sprites_helper::initialize_shader(backend)?;
+        let (vao, instance_buffer) = sprites_helper::initialize_quad(backend)?;
+        let tex = backend.create_texture(texture_image, View::DEFAULT_TEXTURE_FILTER.is_linear())?;
+
+        Ok((program, vao, instance_buffer, tex))
+    }
+
+    //The background tile map is divided into the grid of its atlas, the map data of a level refers to the cells row by row
+    pub(crate) fn get_tile_grid(tile_map: &image::RgbaImage, tile_atlas: &SpriteAtlas) -> Result<(u32, u32), String>
+    {
+        let tile_grid = tile_atlas.get_grid().ok_or("The atlas of a tile map needs a grid")?;
+        tile_atlas.check_image_size(tile_map.width(), tile_map.height())?;
+        Ok(tile_grid)
+    }
+
+    pub fn set_tile_map(&mut self, tile_map: image::RgbaImage, tile_atlas: SpriteAtlas) -> Result<(), String>
+    {
+        let tile_grid = View::get_tile_grid(&tile_map, &tile_atlas)?;
+
+        let tex = self.backend.create_texture(tile_map, self.texture_filter.is_linear())?;
+        self.backend.delete_texture(self.background_tile_texture);
+        self.background_tile_texture = tex;
+        background_helper::set_tile_map_uniforms(self.backend.as_mut(), self.background_shader, tile_grid.0 as f32, tile_grid.1 as f32)?;
+        Ok(())
     }
 
-    fn init_sprite_renderer(context: &WebGl2RenderingContext, texture_image: image::RgbaImage) -> Result<(WebGlProgram, WebGlTexture), String>
+    pub fn set_sprite_tile_map(&mut self, sprite_tile_map: image::RgbaImage, sprite_atlas: SpriteAtlas) -> Result<(), String>
     {
-        let program = sprites_helper::initialize_shader(&context)?;
-        let tex = shader_utils::initialize_texture(context, texture_image, &program, true)?;
-        sprites_helper::set_tile_map_uniforms(context, &program, 10.0, 10.0)?;
+        sprite_atlas.check_image_size(sprite_tile_map.width(), sprite_tile_map.height())?;
 
-        Ok((program, tex))
+        let tex = self.backend.create_texture(sprite_tile_map, self.texture_filter.is_linear())?;
+        self.backend.delete_texture(self.sprite_texture);
+        self.sprite_texture = tex;
+        self.sprite_atlas = sprite_atlas;
+        Ok(())
     }
 
-    fn init_particles_renderer(context: &WebGl2RenderingContext) -> Result<WebGlProgram, String>
+    fn init_particles_renderer(backend: &mut dyn RenderBackend) -> Result<(ProgramId, VertexArrayId, BufferId), String>
     {
-        let program = particles_helper::initialize_shader(context)?;
-        Ok(program)
+        let program = particles_helper::initialize_shader(backend)?;
+        let (vao, instance_buffer) = particles_helper::initialize_quad(backend)?;
+        Ok((program, vao, instance_buffer))
     }
 
-    fn init_render_texture(context: &WebGl2RenderingContext, width: i32, height: i32) -> Result<WebGlTexture, String>
+    fn init_post_process_shaders(&mut self) -> Result<(), String>
     {
-        let tex = context.create_texture().ok_or("failed to create texture")?;
+        for effect in PostProcessEffects::ALL.iter()
+        {
+            self.post_process_effect_shaders.insert(*effect, post_process_effect::get_shader_by_type(self.backend.as_mut(), effect)?);
+        }
+
+        Ok(())
+    }
     
-        context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&tex));
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_S, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_T, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MIN_FILTER, WebGl2RenderingContext::NEAREST as i32);
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MAG_FILTER, WebGl2RenderingContext::NEAREST as i32);
-        
-        context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&tex));
-        match context.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
-            WebGl2RenderingContext::TEXTURE_2D,
-            0,
-            WebGl2RenderingContext::RGBA as i32,
-            width,
-            height,
-            0,
-            WebGl2RenderingContext::RGBA,
-            WebGl2RenderingContext::UNSIGNED_BYTE,
-            None
-            )
+    fn render_background(&mut self)
+    {
+        self.backend.use_program(self.background_shader);
+        self.backend.bind_vertex_array(self.background_vao);
+        self.backend.bind_texture(self.background_tile_texture);
+        self.backend.bind_texture_to_unit(self.background_map_texture, background_helper::MAP_TEXTURE_UNIT);
+
+        self.backend.draw_triangles(0, self.background_triangle_count);
+    }
+
+    fn render_sprites(&mut self)
+    {
+        self.backend.use_program(self.sprite_shader);
+        self.backend.enable_alpha_blending();
+        self.backend.bind_vertex_array(self.sprite_vao);
+        self.backend.bind_texture(self.sprite_texture);
+
+        self.backend.draw_triangles_instanced(0, 6, self.sprite_count);
+    }
+
+    fn render_particles(&mut self)
+    {
+        self.backend.use_program(self.particles_shader);
+        self.backend.enable_alpha_blending();
+        self.backend.bind_vertex_array(self.particles_vao);
+
+        self.backend.draw_triangles_instanced(0, 6, self.particle_systems_count);
+    }
+
+    //Multiplies the light over everything drawn so far
+    fn render_lighting(&mut self)
+    {
+        if !self.lighting_enabled
+        {
+            return;
+        }
+
+        self.backend.use_program(self.lighting_shader);
+        self.backend.enable_multiply_blending();
+        self.backend.bind_vertex_array(self.background_vao);
+        self.backend.bind_texture_to_unit(self.background_map_texture, background_helper::MAP_TEXTURE_UNIT);
+        self.backend.bind_texture_to_unit(self.lights_texture, lighting_helper::LIGHTS_TEXTURE_UNIT);
+        self.backend.bind_texture_to_unit(self.occluding_tiles_texture, lighting_helper::OCCLUDING_TILES_TEXTURE_UNIT);
+
+        self.backend.draw_triangles(0, self.background_triangle_count);
+        self.backend.enable_alpha_blending();
+    }
+
+    //Returns the texture with the finished scene
+    fn apply_post_process_effects(&mut self) -> TextureId
+    {
+        self.post_process_chain.apply(self.backend.as_mut(), &self.post_process_effects, &self.post_process_effect_shaders, self.scene_texture)
+    }
+
+    fn clear_scene(&mut self)
+    {
+        self.backend.bind_render_target(Some(self.scene_framebuffer));
+        self.backend.set_viewport(0, 0, self.scene_width, self.scene_height);
+        self.backend.clear([0.0, 0.0, 0.0, 1.0]);
+    }
+
+    //Clears the whole canvas, so the bars around the viewport are black, and scales the scene up into the viewport
+    fn present_scene(&mut self, scene: TextureId)
+    {
+        self.backend.bind_render_target(None);
+        self.backend.set_viewport(0, 0, self.canvas_width, self.canvas_height);
+        self.backend.clear([0.0, 0.0, 0.0, 1.0]);
+        self.backend.set_viewport(self.viewport.x, self.viewport.y, self.viewport.width, self.viewport.height);
+
+        match (&self.transition, self.snapshot)
         {
-            Ok(x) => Ok(x),
-            Err(_) => Err("failed to initialize render texture"),
-        }?;
+            (Some(_), Some((_, snapshot_texture))) =>
+            {
+                self.backend.use_program(self.transition_shader);
+                self.backend.bind_texture_to_unit(snapshot_texture, transition_helper::SNAPSHOT_TEXTURE_UNIT);
+            },
+            _ => self.backend.use_program(self.upscale_shader),
+        }
+        self.backend.bind_vertex_array(self.background_vao);
+        self.backend.bind_texture(scene);
+        self.backend.draw_triangles(0, self.background_triangle_count);
+        self.last_scene_texture = scene;
+    }
 
-        context.framebuffer_texture_2d(WebGl2RenderingContext::FRAMEBUFFER, WebGl2RenderingContext::COLOR_ATTACHMENT0, WebGl2RenderingContext::TEXTURE_2D, Some(&tex), 0);
+    //Keeps the last frame, so a transition can show it while the next scene is already running. Has to be taken before the
+    //next level is loaded, which might change the size of the scene.
+    pub fn snapshot_scene(&mut self) -> Result<(), String>
+    {
+        let (framebuffer, texture) = self.backend.create_render_target(self.scene_width, self.scene_height, self.texture_filter.is_linear())?;
+        self.delete_snapshot();
+
+        self.backend.bind_render_target(Some(framebuffer));
+        self.backend.set_viewport(0, 0, self.scene_width, self.scene_height);
+        self.backend.use_program(self.upscale_shader);
+        self.backend.bind_vertex_array(self.background_vao);
+        self.backend.bind_texture(self.last_scene_texture);
+        self.backend.draw_triangles(0, self.background_triangle_count);
+        self.backend.bind_render_target(None);
+
+        self.snapshot = Some((framebuffer, texture));
+        Ok(())
+    }
 
-        unsafe
+    fn delete_snapshot(&mut self)
+    {
+        if let Some((framebuffer, texture)) = self.snapshot.take()
         {
-            let draw_buffer = js_sys::Uint32Array::view(&[WebGl2RenderingContext::COLOR_ATTACHMENT0]);
-            context.draw_buffers(draw_buffer.as_ref());
+            self.backend.delete_render_target(framebuffer, texture);
         }
+    }
 
-        Ok(tex)
+    //Runs from the snapshot to the current scene
+    pub fn start_transition(&mut self, transition: Transition) -> Result<(), String>
+    {
+        if self.snapshot.is_none()
+        {
+            return Err("A transition needs a snapshot of the old scene".to_string());
+        }
+
+        self.transition = Some(transition);
+        self.update_transition(0.0)
+    }
+
+    pub fn is_transition_running(&self) -> bool
+    {
+        self.transition.is_some()
     }
 
-    fn init_post_process_shaders(&mut self, context: &WebGl2RenderingContext) -> Result<(), String>
+    fn update_transition(&mut self, delta_time: f32) -> Result<(), String>
     {
-        let all_effects = vec![ PostProcessEffects::VIGNETTE ];
+        let mut transition = match self.transition
+        {
+            Some(transition) => transition,
+            None => return Ok(()),
+        };
 
-        for effect in all_effects.iter()
+        transition.running_time += delta_time;
+        if transition.is_finished()
         {
-            self.post_process_effect_shaders.insert(*effect, post_process_effect::get_shader_by_type(context, effect)?);
+            self.transition = None;
+            self.delete_snapshot();
+            return Ok(());
         }
 
+        let program = self.transition_shader;
+        self.backend.set_uniform(program, "kind", UniformValue::Int(transition.kind as i32))?;
+        self.backend.set_uniform(program, "progress", UniformValue::Float(transition.get_progress()))?;
+        self.backend.set_uniform(program, "oldCenter", UniformValue::Vec2(transition.old_center))?;
+        self.backend.set_uniform(program, "newCenter", UniformValue::Vec2(transition.new_center))?;
+        self.backend.set_uniform(program, "aspect", UniformValue::Float(self.viewport.width as f32 / self.viewport.height as f32))?;
+        self.transition = Some(transition);
         Ok(())
     }
-    
-    fn render_background(&self, context: &WebGl2RenderingContext)
+
+    //A map of the same size as the current one reuses its texture
+    pub fn update_map(&mut self, new_map: LevelViewModel) -> Result<(), String>
     {
-        context.use_program(Some(&self.background_shader));
-        context.bind_vertex_array(Some(&self.background_vao));
-        context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&self.background_tile_texture));
+        let width = new_map.width as u32;
+        let height = new_map.height as u32;
+        if width == 0 || height == 0 || new_map.data.len() != (width * height) as usize
+        {
+            return Err(format!("Map of {}x{} tiles has {} tiles", width, height, new_map.data.len()));
+        }
 
-        context.draw_arrays(
-            WebGl2RenderingContext::TRIANGLES,
-            0,
-            self.background_triangle_count,
-        );
+        if width == self.map_width && height == self.map_height
+        {
+            return self.backend.update_data_texture(self.background_map_texture, 0, 0, width, height, &new_map.data);
+        }
+
+        let tex = self.backend.create_data_texture(width, height, &new_map.data)?;
+        self.backend.delete_texture(self.background_map_texture);
+        self.background_map_texture = tex;
+        self.map_width = width;
+        self.map_height = height;
+        background_helper::update_map_size(self.backend.as_mut(), self.background_shader, width, height)?;
+        background_helper::update_map_size(self.backend.as_mut(), self.lighting_shader, width, height)?;
+        //Tiles stay square, so the aspect ratio of the viewport follows the map
+        self.update_viewport()
     }
 
-    fn render_sprites(&self, context: &WebGl2RenderingContext)
+    //Size of the canvas in pixels
+    pub fn resize(&mut self, width: i32, height: i32) -> Result<(), String>
     {
-        context.use_program(Some(&self.sprite_shader));
-        context.enable(WebGl2RenderingContext::BLEND);
-        context.blend_func(WebGl2RenderingContext::SRC_ALPHA, WebGl2RenderingContext::ONE_MINUS_SRC_ALPHA);
-        context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&self.sprite_texture));
+        if width <= 0 || height <= 0
+        {
+            return Err(format!("Invalid canvas size {}x{}", width, height));
+        }
 
-        context.draw_arrays(
-            WebGl2RenderingContext::TRIANGLES,
-            0,
-            self.sprite_count * 6,
-        );
+        self.canvas_width = width;
+        self.canvas_height = height;
+        self.update_viewport()
     }
 
-    fn render_particles(&self, context: &WebGl2RenderingContext)
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) -> Result<(), String>
     {
-        context.use_program(Some(&self.particles_shader));
-        context.enable(WebGl2RenderingContext::BLEND);
-        context.blend_func(WebGl2RenderingContext::SRC_ALPHA, WebGl2RenderingContext::ONE_MINUS_SRC_ALPHA);
+        self.scale_mode = scale_mode;
+        self.update_viewport()
+    }
 
-        context.draw_arrays(
-            WebGl2RenderingContext::TRIANGLES,
-            0,
-            self.particle_systems_count * 6,
-        );
+    //Renders the scene into a target of at most this many pixels, which is then scaled up to the canvas, e.g. 320x180
+    //for crisp pixel art with the integer scale mode. The target keeps the aspect ratio of the map, so tiles stay square.
+    //None renders the scene at the size of the viewport.
+    pub fn set_render_resolution(&mut self, resolution: Option<(i32, i32)>) -> Result<(), String>
+    {
+        if let Some((width, height)) = resolution
+        {
+            if width <= 0 || height <= 0
+            {
+                return Err(format!("Invalid render resolution {}x{}", width, height));
+            }
+        }
+
+        self.render_resolution = resolution;
+        self.update_viewport()
     }
 
-    fn apply_post_process_effects(&self, context: &WebGl2RenderingContext)
+    //Used for the tile maps and when the scene is scaled up to the canvas
+    pub fn set_texture_filter(&mut self, texture_filter: TextureFilter)
     {
-        for effect in self.post_process_effects.iter()
+        self.texture_filter = texture_filter;
+        for texture in [ self.background_tile_texture, self.sprite_texture, self.scene_texture ].iter()
         {
-            effect.apply(context, &self.render_texture, self.post_process_effect_shaders.get(&effect.get_effect_type()).unwrap());
+            self.backend.set_texture_filter(*texture, texture_filter.is_linear());
         }
+        self.post_process_chain.set_texture_filter(self.backend.as_mut(), texture_filter.is_linear());
+    }
+
+    //Effects run in this order, effects missing in it run after the others
+    pub fn set_post_process_effect_order(&mut self, order: std::vec::Vec<PostProcessEffects>)
+    {
+        self.post_process_chain.set_order(order);
+    }
+
+    //A disabled effect is skipped, e.g. for players who do not like the screen to flash
+    pub fn set_post_process_effect_enabled(&mut self, effect_type: PostProcessEffects, enabled: bool)
+    {
+        self.post_process_chain.set_enabled(effect_type, enabled);
     }
 
-    fn clear_screen(&self, context: &WebGl2RenderingContext)
+    pub fn get_viewport(&self) -> Viewport
     {
-        context.clear_color(0.0, 0.0, 0.0, 1.0);
-        context.clear(WebGl2RenderingContext::COLOR_BUFFER_BIT);
+        self.viewport
     }
 
-    pub fn update_map(&self, context: &WebGl2RenderingContext, new_map: LevelViewModel) -> Result<(), String>
+    //Size of the scene render target in pixels
+    pub fn get_scene_size(&self) -> (i32, i32)
     {
+        (self.scene_width, self.scene_height)
+    }
 
-        context.use_program(Some(&self.background_shader));
-        let loc = context.get_uniform_location(&self.background_shader, "map").ok_or("Failed to get location of map")?;
-        context.uniform1iv_with_i32_array(Some(&loc), &new_map.data);
+    //The render targets cover the scene, so they are recreated whenever the scene changes its size
+    fn update_viewport(&mut self) -> Result<(), String>
+    {
+        let base_width = self.map_width as i32 * View::PIXELS_PER_TILE;
+        let base_height = self.map_height as i32 * View::PIXELS_PER_TILE;
+        let scene = match self.render_resolution
+        {
+            Some((width, height)) => ScaleMode::Letterbox.get_viewport(width, height, base_width, base_height),
+            None => self.scale_mode.get_viewport(self.canvas_width, self.canvas_height, base_width, base_height),
+        };
+        //Never smaller than a pixel, e.g. for a tall map in a very flat resolution
+        let scene_width = scene.width.max(1);
+        let scene_height = scene.height.max(1);
 
-        shader_utils::set_uniform1f(context, &self.background_shader, new_map.width, "width")?;
-        shader_utils::set_uniform1f(context, &self.background_shader, new_map.height, "height")?;
+        if scene_width != self.scene_width || scene_height != self.scene_height
+        {
+            self.post_process_chain.resize(self.backend.as_mut(), scene_width, scene_height, self.texture_filter.is_linear())?;
+
+            let (scene_framebuffer, scene_texture) = self.backend.create_render_target(scene_width, scene_height, self.texture_filter.is_linear())?;
+            self.backend.delete_render_target(self.scene_framebuffer, self.scene_texture);
+            self.scene_framebuffer = scene_framebuffer;
+            self.scene_texture = scene_texture;
+            self.scene_width = scene_width;
+            self.scene_height = scene_height;
+            self.last_scene_texture = scene_texture;
+        }
 
+        //Without a render resolution the scene already has the size of the viewport, so this is the same viewport again
+        self.viewport = self.scale_mode.get_viewport(self.canvas_width, self.canvas_height, scene_width, scene_height);
         Ok(())
     }
 
-    pub fn update_sprites(&mut self, context: &WebGl2RenderingContext, updated_sprites: SpritesViewModel) -> Result<(), String>
+    //Changes a single tile of the current map, e.g. when a door opens, without uploading the whole map again
+    pub fn set_map_tile(&mut self, col: u32, row: u32, tile: i32) -> Result<(), String>
     {
-        sprites_helper::update_sizes(context, &self.sprite_shader, updated_sprites.sizes)?;
-        sprites_helper::update_positions(context, &self.sprite_shader, updated_sprites.positions)?;
-        sprites_helper::update_tile_map_indices(context, &self.sprite_shader, updated_sprites.tile_map_indices)?;
-        self.sprite_count = updated_sprites.count;
+        if col >= self.map_width || row >= self.map_height
+        {
+            return Err(format!("Tile {}, {} is outside the map of {}x{} tiles", col, row, self.map_width, self.map_height));
+        }
+
+        self.backend.update_data_texture(self.background_map_texture, col, row, 1, 1, &[tile])
+    }
+
+    pub fn update_sprites(&mut self, updated_sprites: SpritesViewModel) -> Result<(), String>
+    {
+        let mut uv_rects: std::vec::Vec<cgmath::Vector4<f32>> = std::vec::Vec::with_capacity(updated_sprites.get_count());
+        for frame in updated_sprites.frames.iter()
+        {
+            let rect = self.sprite_atlas.get_uv_rect(*frame)?;
+            uv_rects.push(cgmath::Vector4{ x: rect.min_x, y: rect.min_y, z: rect.max_x, w: rect.max_y });
+        }
+        sprites_helper::update_instances(self.backend.as_mut(), self.sprite_instance_buffer, &updated_sprites.positions, &updated_sprites.sizes, &uv_rects, &updated_sprites.alphas);
+        self.sprite_count = updated_sprites.get_count() as i32;
         Ok(())
     }
 
-    pub fn update_particle_systems(&mut self, context: &WebGl2RenderingContext, updated_particles: ParticlesViewModel) -> Result<(), String>
+    pub fn update_particle_systems(&mut self, updated_particles: ParticlesViewModel) -> Result<(), String>
     {
-        particles_helper::update_positions(context, &self.particles_shader, updated_particles.positions)?;
-        particles_helper::update_max_speeds(context, &self.particles_shader, updated_particles.max_speeds)?;
-        particles_helper::update_running_times(context, &self.particles_shader, updated_particles.running_times)?;
-        particles_helper::update_max_running_times(context, &self.particles_shader, updated_particles.max_running_times)?;
-        self.particle_systems_count = updated_particles.count;
+        particles_helper::update_instances(self.backend.as_mut(), self.particles_instance_buffer, &updated_particles.positions, &updated_particles.max_speeds, &updated_particles.running_times, &updated_particles.max_running_times);
+        self.particle_systems_count = updated_particles.get_count() as i32;
         Ok(())
     }
     
+    //Updates the data texture if the size stays the same, otherwise it is replaced by a new one
+    fn upload_data_texture(&mut self, texture: TextureId, old_size: (u32, u32), size: (u32, u32), data: &[i32]) -> Result<TextureId, String>
+    {
+        if size == old_size
+        {
+            self.backend.update_data_texture(texture, 0, 0, size.0, size.1, data)?;
+            return Ok(texture);
+        }
+
+        let new_texture = self.backend.create_data_texture(size.0, size.1, data)?;
+        self.backend.delete_texture(texture);
+        Ok(new_texture)
+    }
+
+    //None switches the lighting pass off, e.g. for levels in daylight
+    pub fn update_lights(&mut self, lights: Option<LightsViewModel>) -> Result<(), String>
+    {
+        let lights = match lights
+        {
+            Some(lights) => lights,
+            None =>
+            {
+                self.lighting_enabled = false;
+                return Ok(());
+            },
+        };
+
+        let light_rows = (lights.get_count() as u32).max(1);
+        self.lights_texture = self.upload_data_texture(self.lights_texture, (lighting_helper::LIGHT_VALUES, self.light_rows), (lighting_helper::LIGHT_VALUES, light_rows), &lighting_helper::get_texture_data(&lights))?;
+        self.light_rows = light_rows;
+
+        //At least one texel, which the shader skips without occluding tiles
+        let occluding_tiles = if lights.occluding_tiles.is_empty() { vec![ 0 ] } else { lights.occluding_tiles.clone() };
+        let occluding_tile_columns = occluding_tiles.len() as u32;
+        self.occluding_tiles_texture = self.upload_data_texture(self.occluding_tiles_texture, (self.occluding_tile_columns, 1), (occluding_tile_columns, 1), &occluding_tiles)?;
+        self.occluding_tile_columns = occluding_tile_columns;
+
+        lighting_helper::update_counts(self.backend.as_mut(), self.lighting_shader, lights.ambient, lights.get_count(), lights.occluding_tiles.len())?;
+        self.lighting_enabled = true;
+        Ok(())
+    }
+
+    //Background, sprites, particles and lights are drawn as seen by the camera, post process effects always cover the screen
+    pub fn update_camera(&mut self, camera: CameraViewModel) -> Result<(), String>
+    {
+        for program in [ self.background_shader, self.sprite_shader, self.particles_shader, self.lighting_shader ].iter()
+        {
+            self.backend.set_uniform(*program, "cameraPosition", UniformValue::Vec2(camera.position))?;
+            self.backend.set_uniform(*program, "cameraZoom", UniformValue::Float(camera.zoom))?;
+            self.backend.set_uniform(*program, "cameraRotation", UniformValue::Float(camera.rotation))?;
+        }
+        Ok(())
+    }
+
     pub fn update_post_process_effects(&mut self, updated_post_process_effects: PostProcessViewModel) -> Result<(), String>
     {
         self.post_process_effects.clear();
         for effect in updated_post_process_effects.effects.iter()
         {
-            self.post_process_effects.push(post_process_effect::get_effect_by_type(PostProcessEffects::VIGNETTE, effect.running_time, effect.max_running_time));
+            self.post_process_effects.push(post_process_effect::get_effect_by_type(effect.name, effect.running_time, effect.max_running_time, effect.parameters));
+        }
+        for effect in self.view_effects.iter()
+        {
+            self.post_process_effects.push(post_process_effect::get_effect_by_type(effect.name, effect.running_time, effect.max_running_time, effect.parameters));
         }
         Ok(())
     }
 
-    pub fn update(&mut self, context: &WebGl2RenderingContext, sprites: SpritesViewModel, particles: ParticlesViewModel, post_process_effects: PostProcessViewModel) -> Result<(), String>
+    fn update_view_effects(&mut self, delta_time: f32)
+    {
+        for effect in self.view_effects.iter_mut()
+        {
+            effect.running_time += delta_time;
+        }
+
+        self.view_effects.retain(|x| x.running_time < x.max_running_time);
+    }
+
+    pub fn flash_screen(&mut self, duration: f32)
+    {
+        self.start_effect(PostProcessEffects::FLASH, duration, PostProcessParameters::default_for(PostProcessEffects::FLASH));
+    }
+
+    //Effect started by the view itself rather than the model, a duration of infinity keeps it running, e.g. for a theme
+    pub fn start_effect(&mut self, name: PostProcessEffects, duration: f32, parameters: PostProcessParameters)
+    {
+        self.view_effects.push(PostProcessEffect {
+            name,
+            running_time: 0.0,
+            max_running_time: duration,
+            parameters,
+        });
+    }
+
+    pub fn update(&mut self, sprites: SpritesViewModel, particles: ParticlesViewModel, post_process_effects: PostProcessViewModel, camera: CameraViewModel, delta_time: f32) -> Result<(), String>
     {
-        self.update_sprites(context, sprites)?;
-        self.update_particle_systems(context, particles)?;
+        self.update_view_effects(delta_time);
+        self.update_transition(delta_time)?;
+        self.update_camera(camera)?;
+        self.update_sprites(sprites)?;
+        self.update_particle_systems(particles)?;
         self.update_post_process_effects(post_process_effects)?;
         Ok(())
     }
 
-    pub fn draw(&self, context: &WebGl2RenderingContext)
+    pub fn draw(&mut self)
     {
-        self.clear_screen(context);
-        self.render_background(context);
-        self.render_sprites(context);
-        self.render_particles(context);
-        self.apply_post_process_effects(context);
+        self.clear_scene();
+        self.render_background();
+        self.render_sprites();
+        self.render_particles();
+        self.render_lighting();
+        let scene = self.apply_post_process_effects();
+        self.present_scene(scene);
     }
 }
\ No newline at end of file
@@ -1,36 +1,37 @@
-use crate::view::shader_utils;
+use crate::view::render_backend::{RenderBackend, ProgramId, UniformValue};
 
-use web_sys::{WebGl2RenderingContext, WebGlProgram};
+//The tile map is on unit 0, the map of the level on this one
+pub const MAP_TEXTURE_UNIT: u32 = 1;
 
-pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgram, String> 
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String> 
 {
-    let vert_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::VERTEX_SHADER,
-        r#"#version 300 es
+    let vert_shader = r#"#version 300 es
 
         in vec4 position;
         in vec2 uvIn;
 
+        uniform vec2 cameraPosition;
+        uniform float cameraZoom;
+        uniform float cameraRotation;
+
         out vec2 uvOut;
 
         void main()
         {
             gl_Position = position;
-            uvOut = uvIn;
+            //The part of the level that is on screen, the level spans from -1 to 1. Turned back by the rotation of the camera.
+            mat2 unrotate = mat2(cos(cameraRotation), -sin(cameraRotation), sin(cameraRotation), cos(cameraRotation));
+            uvOut = ((unrotate * position.xy) / cameraZoom + cameraPosition + 1.0) / 2.0;
         }
-    "#,
-    )?;
+    "#;
 
-    let frag_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::FRAGMENT_SHADER,
-        r#"#version 300 es
+    let frag_shader = r#"#version 300 es
         precision highp float;
 
         uniform float width;
         uniform float height;
-        uniform int[20*20] map;
+        //Tile of every map cell, read with texelFetch
+        uniform highp isampler2D map;
 
         uniform float tileMapWidth;
         uniform float tileMapHeight;
@@ -47,8 +48,7 @@ pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgra
 
             float col = floor(width * uv.x);
             float row = floor(height * uv.y);
-            float mapTile = row * width + col;
-            float tileToUse = float(map[int(mapTile)]);
+            float tileToUse = float(texelFetch(map, ivec2(int(col), int(row)), 0).r);
 
             float isOddCol = step(0.5, mod(col, 2.0));
             uv.x = isOddCol * (1.0 - uv.x) + (1.0 - isOddCol) * uv.x;
@@ -57,7 +57,7 @@ pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgra
             uv.y = idOddRow * (1.0 - uv.y) + (1.0 - idOddRow) * uv.y;
 
             float tileToUseCol = mod(tileToUse, tileMapWidth);
-            float tileToUseRow = floor(tileToUse / tileMapHeight);
+            float tileToUseRow = floor(tileToUse / tileMapWidth);
 
             float startTileX = tileToUseCol / tileMapWidth;
             float tileSizeX = 1.0 / tileMapWidth;
@@ -75,21 +75,24 @@ pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgra
             // outColor = texture(tileMap, vec2(0.55, 0.55));
             // outColor = vec4(uv.x, uv.x, uv.x, 1.0);
         }
-    "#,
-    )?;
-
-    return shader_utils::link_program(
-        &context,
-        &vert_shader,
-        &frag_shader,
-        vec![(0, "position"), (1, "uv")],
-    );
+    "#;
+
+    let program = backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uv")])?;
+    backend.set_uniform(program, "tileMap", UniformValue::Int(0))?;
+    backend.set_uniform(program, "map", UniformValue::Int(MAP_TEXTURE_UNIT as i32))?;
+    Ok(program)
 }
 
-pub fn set_tile_map_uniforms(context: &WebGl2RenderingContext, program: &WebGlProgram, width: f32, height: f32) -> Result<(), String>
+pub fn set_tile_map_uniforms(backend: &mut dyn RenderBackend, program: ProgramId, width: f32, height: f32) -> Result<(), String>
 {       
-    context.use_program(Some(&program));
-    shader_utils::set_uniform1f(context, program, width, "tileMapWidth")?;
-    shader_utils::set_uniform1f(context, program, height, "tileMapHeight")?;
+    backend.set_uniform(program, "tileMapWidth", UniformValue::Float(width))?;
+    backend.set_uniform(program, "tileMapHeight", UniformValue::Float(height))?;
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn update_map_size(backend: &mut dyn RenderBackend, program: ProgramId, width: u32, height: u32) -> Result<(), String>
+{
+    backend.set_uniform(program, "width", UniformValue::Float(width as f32))?;
+    backend.set_uniform(program, "height", UniformValue::Float(height as f32))?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::view::render_backend::{RenderBackend, ProgramId, UniformValue};
+use crate::view::background_helper::MAP_TEXTURE_UNIT;
+use crate::view_models::LightsViewModel;
+
+//The map is on the same unit as for the background, the lights and the occluding tiles on these
+pub const LIGHTS_TEXTURE_UNIT: u32 = 2;
+pub const OCCLUDING_TILES_TEXTURE_UNIT: u32 = 3;
+//Values in the row of a light: position, radius and color times intensity
+pub const LIGHT_VALUES: u32 = 6;
+
+//Light of every pixel of the level, multiplied over the scene. The level is covered like by the background, walls are found in the
+//map of the level, which is bound on the same unit as for the background.
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let vert_shader = r#"#version 300 es
+
+        in vec4 position;
+        in vec2 uvIn;
+
+        uniform vec2 cameraPosition;
+        uniform float cameraZoom;
+        uniform float cameraRotation;
+
+        out vec2 uvOut;
+
+        void main()
+        {
+            gl_Position = position;
+            //Same as the background, the level spans from -1 to 1
+            mat2 unrotate = mat2(cos(cameraRotation), -sin(cameraRotation), sin(cameraRotation), cos(cameraRotation));
+            uvOut = ((unrotate * position.xy) / cameraZoom + cameraPosition + 1.0) / 2.0;
+        }
+    "#;
+
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        //Samples on the way from a pixel to a light that are checked for walls
+        const int SHADOW_STEPS = 32;
+
+        uniform float width;
+        uniform float height;
+        uniform highp isampler2D map;
+
+        uniform vec3 ambient;
+        uniform int lightCount;
+        //One row per light with the bits of position, radius and color times intensity, see get_texture_data
+        uniform highp isampler2D lights;
+        uniform int occludingTileCount;
+        //Tiles of the map that block light, in one row
+        uniform highp isampler2D occludingTiles;
+
+        in vec2 uvOut;
+
+        out vec4 outColor;
+
+        float getLightValue(int light, int value)
+        {
+            return intBitsToFloat(texelFetch(lights, ivec2(value, light), 0).r);
+        }
+
+        //In tiles from the top left corner of the map
+        vec2 toTiles(vec2 levelUv)
+        {
+            return vec2(levelUv.x * width, (1.0 - levelUv.y) * height);
+        }
+
+        bool isOccluding(vec2 tile)
+        {
+            if(tile.x < 0.0 || tile.y < 0.0 || tile.x >= width || tile.y >= height)
+            {
+                return false;
+            }
+
+            int tileToUse = texelFetch(map, ivec2(tile), 0).r;
+            for(int i = 0; i < occludingTileCount; i++)
+            {
+                if(texelFetch(occludingTiles, ivec2(i, 0), 0).r == tileToUse)
+                {
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        //The tiles of the pixel and of the light itself do not block, so walls are lit on their own tile
+        float getVisibility(vec2 pixel, vec2 light)
+        {
+            vec2 pixelTile = floor(pixel);
+            vec2 lightTile = floor(light);
+            for(int i = 1; i < SHADOW_STEPS; i++)
+            {
+                vec2 tile = floor(mix(pixel, light, float(i) / float(SHADOW_STEPS)));
+                if(tile != pixelTile && tile != lightTile && isOccluding(tile))
+                {
+                    return 0.0;
+                }
+            }
+            return 1.0;
+        }
+
+        void main()
+        {
+            vec2 pixel = toTiles(uvOut);
+            vec3 light = ambient;
+
+            for(int i = 0; i < lightCount; i++)
+            {
+                vec2 lightPosition = toTiles((vec2(getLightValue(i, 0), getLightValue(i, 1)) + 1.0) / 2.0);
+                float falloff = max(1.0 - distance(pixel, lightPosition) / getLightValue(i, 2), 0.0);
+                if(falloff > 0.0)
+                {
+                    vec3 color = vec3(getLightValue(i, 3), getLightValue(i, 4), getLightValue(i, 5));
+                    light += color * falloff * falloff * getVisibility(pixel, lightPosition);
+                }
+            }
+
+            outColor = vec4(min(light, vec3(1.0)), 1.0);
+        }
+    "#;
+
+    let program = backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uvIn")])?;
+    backend.set_uniform(program, "map", UniformValue::Int(MAP_TEXTURE_UNIT as i32))?;
+    backend.set_uniform(program, "lights", UniformValue::Int(LIGHTS_TEXTURE_UNIT as i32))?;
+    backend.set_uniform(program, "occludingTiles", UniformValue::Int(OCCLUDING_TILES_TEXTURE_UNIT as i32))?;
+    backend.set_uniform(program, "lightCount", UniformValue::Int(0))?;
+    backend.set_uniform(program, "occludingTileCount", UniformValue::Int(0))?;
+    Ok(program)
+}
+
+//The lights are floats in an integer texture like the map, so they are stored as their bits. Any number of lights fits, there is
+//always at least one row, which the shader skips without lights.
+pub fn get_texture_data(lights: &LightsViewModel) -> std::vec::Vec<i32>
+{
+    let mut data: std::vec::Vec<i32> = std::vec::Vec::with_capacity(lights.get_count().max(1) * LIGHT_VALUES as usize);
+    for i in 0..lights.get_count()
+    {
+        let color = lights.colors[i] * lights.intensities[i];
+        for value in [ lights.positions[i].x, lights.positions[i].y, lights.radii[i], color.x, color.y, color.z ].iter()
+        {
+            data.push(value.to_bits() as i32);
+        }
+    }
+    data.resize(data.len().max(LIGHT_VALUES as usize), 0);
+    data
+}
+
+pub fn update_counts(backend: &mut dyn RenderBackend, program: ProgramId, ambient: cgmath::Vector3<f32>, light_count: usize, occluding_tile_count: usize) -> Result<(), String>
+{
+    backend.set_uniform(program, "ambient", UniformValue::Vec3(ambient))?;
+    backend.set_uniform(program, "lightCount", UniformValue::Int(light_count as i32))?;
+    backend.set_uniform(program, "occludingTileCount", UniformValue::Int(occluding_tile_count as i32))?;
+    Ok(())
+}
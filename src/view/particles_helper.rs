@@ -1,90 +1,51 @@
-use crate::view::shader_utils;
+use crate::view::render_backend::{RenderBackend, ProgramId, VertexArrayId, BufferId};
 
 use cgmath;
-use web_sys::{WebGl2RenderingContext, WebGlProgram};
 
-pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgram, String> 
+//Per instance attributes as (location, number of floats): center, max speed, running time and max running time
+pub const INSTANCE_ATTRIBUTES: [(u32, i32);4] = [ (2, 2), (3, 1), (4, 1), (5, 1) ];
+
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String> 
 {
-    let vert_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::VERTEX_SHADER,
-        r#"#version 300 es
-
-        uniform vec2[10] positions;
-        uniform float[10] numberOfParticles;
-        uniform float[10] seed;
-        uniform float[10] maxSpeeds;
-        uniform float[10] runningTimes;
-        uniform float[10] maxRunningTimes;
-        uniform float[10] tileMapIndices;
+    let vert_shader = r#"#version 300 es
+
+        in vec4 position;
+        in vec2 uvIn;
+
+        in vec2 center;
+        in float maxSpeed;
+        in float runningTime;
+        in float maxRunningTime;
+
+        uniform vec2 cameraPosition;
+        uniform float cameraZoom;
+        uniform float cameraRotation;
 
         out vec2 uv;
-        flat out int idx;
-        flat out int tileMapIndex;
-        flat out vec2 centerPos;
+        flat out float alpha;
 
         const float sizeAdjust = 1.4142; // 1 / cos(45°) -> Convert half diagonal of quad to cover a circular area of r = half diagonal
 
         void main()
         {
-            idx = gl_VertexID / 6;
-            centerPos = positions[idx];
-            float maxSize = maxSpeeds[idx] * runningTimes[idx];
-            tileMapIndex = int(tileMapIndices[idx]);
-            
-            int subIdx = gl_VertexID % 6;
-            
-            if(subIdx == 0)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(1, -1) * sizeAdjust, 0, 1);
-                uv = vec2(1.0, 0.0);
-            }
-            else if(subIdx == 1)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(1, 1) * sizeAdjust, 0, 1);
-                uv = vec2(1.0, 1.0);
-            }
-            else if(subIdx == 2)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(-1, 1) * sizeAdjust, 0, 1);
-                uv = vec2(0.0, 1.0);
-            }
-            else if(subIdx == 3)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(1, -1) * sizeAdjust, 0, 1);
-                uv = vec2(1.0, 0.0);
-            }
-            else if(subIdx == 4)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(-1, 1) * sizeAdjust, 0, 1);
-                uv = vec2(0.0, 1.0);
-            }
-            else// if(subIdx == 5)
-            {
-                gl_Position = vec4(centerPos + maxSize * vec2(-1, -1) * sizeAdjust, 0, 1);
-                uv = vec2(0.0, 0.0);
-            }
+            //The quad spans from -1 to 1
+            float maxSize = maxSpeed * runningTime;
+            mat2 rotate = mat2(cos(cameraRotation), sin(cameraRotation), -sin(cameraRotation), cos(cameraRotation));
+            gl_Position = vec4(rotate * ((center + maxSize * position.xy * sizeAdjust - cameraPosition) * cameraZoom), 0, 1);
+            uv = uvIn;
+            alpha = 1.0 - (runningTime / maxRunningTime);
         }
-    "#,
-    )?;
+    "#;
 
-    let frag_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::FRAGMENT_SHADER,
-        r#"#version 300 es
+    let frag_shader = r#"#version 300 es
         precision highp float;
 
-        uniform float[10] maxRunningTimes;
-        uniform float[10] runningTimes;
-
         uniform float tileMapWidth;
         uniform float tileMapHeight;
         uniform sampler2D tileMap;
         
         in vec2 uv;
-        flat in int idx;
-        flat in int tileMapIndex;
-        flat in vec2 centerPos;
+        flat in float alpha;
 
         out vec4 outColor;
 
@@ -106,41 +67,28 @@ pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgra
 
             // outColor = texture(tileMap, vec2(tiledUvX, tiledUvY));
 
-            outColor = vec4(1.0, 0.0, 0.0, 1.0 - (runningTimes[idx] / maxRunningTimes[idx]));
+            outColor = vec4(1.0, 0.0, 0.0, alpha);
         }
-    "#,
-    )?;
-
-    return shader_utils::link_program(
-        &context,
-        &vert_shader,
-        &frag_shader,
-        vec![(0, "position"), (1, "uv")],
-    );
-}
+    "#;
 
-pub fn update_positions(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_positions: [cgmath::Vector2<f32>;10]) -> Result<(), String>
-{
-    shader_utils::set_uniform2f_arr10(context, shader, new_positions, "positions")?;
-    Ok(())
-}
-
-pub fn update_max_speeds(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_max_speeds: [f32;10]) -> Result<(), String>
-{
-    shader_utils::set_uniform1f_arr10(context, shader, new_max_speeds, "maxSpeeds")?;
-    Ok(())
+    backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uvIn"), (2, "center"), (3, "maxSpeed"), (4, "runningTime"), (5, "maxRunningTime")])
 }
 
-pub fn update_running_times(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_running_times: [f32;10]) -> Result<(), String>
+pub fn initialize_quad(backend: &mut dyn RenderBackend) -> Result<(VertexArrayId, BufferId), String>
 {
-    shader_utils::set_uniform1f_arr10(context, shader, new_running_times, "runningTimes")?;
-    Ok(())
+    let (_, vao, instance_buffer) = backend.create_instanced_quad(cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 2.0, y: 2.0 }, &INSTANCE_ATTRIBUTES)?;
+    Ok((vao, instance_buffer))
 }
 
-pub fn update_max_running_times(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_max_running_times: [f32;10]) -> Result<(), String>
+//Uploads the particle systems in the layout of INSTANCE_ATTRIBUTES
+pub fn update_instances(backend: &mut dyn RenderBackend, instance_buffer: BufferId, positions: &[cgmath::Vector2<f32>], max_speeds: &[f32], running_times: &[f32], max_running_times: &[f32])
 {
-    shader_utils::set_uniform1f_arr10(context, shader, new_max_running_times, "maxRunningTimes")?;
-    Ok(())
+    let mut data: std::vec::Vec<f32> = std::vec::Vec::with_capacity(positions.len() * 5);
+    for i in 0..positions.len()
+    {
+        data.extend_from_slice(&[ positions[i].x, positions[i].y, max_speeds[i], running_times[i], max_running_times[i] ]);
+    }
+    backend.update_instance_buffer(instance_buffer, &data);
 }
 
 // pub fn update_tile_map_indices(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_indices: [i32;10]) -> Result<(), String>
@@ -1,28 +1,116 @@
 pub mod effect;
+pub mod chain;
 pub mod vignette;
+pub mod flash;
+pub mod crt;
+pub mod chromatic_aberration;
+pub mod pixelate;
+pub mod desaturate;
 
-use crate::view_models::PostProcessEffects;
-use web_sys::{WebGlProgram, WebGl2RenderingContext};
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId};
 
+//Full screen quad without vertex data, every effect reads the scene at the uv
+pub const VERTEX_SHADER: &str = r#"#version 300 es
 
-pub fn get_shader_by_type(context: &WebGl2RenderingContext, effect_type: &PostProcessEffects) -> Result<WebGlProgram, String>
+        out vec2 uv;
+
+        void main()
+        {
+            int subIdx = gl_VertexID % 6;
+            
+            if(subIdx == 0)
+            {
+                gl_Position = vec4(1, -1, 0, 1);
+                uv = vec2(1.0, 0.0);
+            }
+            else if(subIdx == 1)
+            {
+                gl_Position = vec4(1, 1, 0, 1);
+                uv = vec2(1.0, 1.0);
+            }
+            else if(subIdx == 2)
+            {
+                gl_Position = vec4(-1, 1, 0, 1);
+                uv = vec2(0.0, 1.0);
+            }
+            else if(subIdx == 3)
+            {
+                gl_Position = vec4(1, -1, 0, 1);
+                uv = vec2(1.0, 0.0);
+            }
+            else if(subIdx == 4)
+            {
+                gl_Position = vec4(-1, 1, 0, 1);
+                uv = vec2(0.0, 1.0);
+            }
+            else// if(subIdx == 5)
+            {
+                gl_Position = vec4(-1, -1, 0, 1);
+                uv = vec2(0.0, 0.0);
+            }
+        }
+    "#;
+
+
+pub fn get_shader_by_type(backend: &mut dyn RenderBackend, effect_type: &PostProcessEffects) -> Result<ProgramId, String>
 {
     match effect_type
     {
         PostProcessEffects::VIGNETTE =>
         {
-            vignette::get_shader(context)
+            vignette::get_shader(backend)
+        }
+        PostProcessEffects::FLASH =>
+        {
+            flash::get_shader(backend)
+        }
+        PostProcessEffects::CRT =>
+        {
+            crt::get_shader(backend)
+        }
+        PostProcessEffects::CHROMATIC_ABERRATION =>
+        {
+            chromatic_aberration::get_shader(backend)
+        }
+        PostProcessEffects::PIXELATE =>
+        {
+            pixelate::get_shader(backend)
+        }
+        PostProcessEffects::DESATURATE =>
+        {
+            desaturate::get_shader(backend)
         }
     }
 }
 
-pub fn get_effect_by_type(effect_type: PostProcessEffects, running_time: f32, max_running_time: f32) -> Box<dyn effect::Effect>
+pub fn get_effect_by_type(effect_type: PostProcessEffects, running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> Box<dyn effect::Effect>
 {
     match effect_type
     {
         PostProcessEffects::VIGNETTE => 
         {
-            Box::new(vignette::Vignette::new(running_time, max_running_time))
+            Box::new(vignette::Vignette::new(running_time, max_running_time, parameters))
+        }
+        PostProcessEffects::FLASH => 
+        {
+            Box::new(flash::Flash::new(running_time, max_running_time, parameters))
+        }
+        PostProcessEffects::CRT => 
+        {
+            Box::new(crt::Crt::new(running_time, max_running_time, parameters))
+        }
+        PostProcessEffects::CHROMATIC_ABERRATION => 
+        {
+            Box::new(chromatic_aberration::ChromaticAberration::new(running_time, max_running_time, parameters))
+        }
+        PostProcessEffects::PIXELATE => 
+        {
+            Box::new(pixelate::Pixelate::new(running_time, max_running_time, parameters))
+        }
+        PostProcessEffects::DESATURATE => 
+        {
+            Box::new(desaturate::Desaturate::new(running_time, max_running_time, parameters))
         }
     }
 }
\ No newline at end of file
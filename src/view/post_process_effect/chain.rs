@@ -0,0 +1,101 @@
+use crate::view::post_process_effect::effect::Effect;
+use crate::view_models::PostProcessEffects;
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId, FramebufferId};
+
+//Runs the post process effects one after another. Every effect reads the result of the one before and draws into the other one
+//of two render targets, so effects stack. The last result is scaled up to the canvas by the view.
+pub struct PostProcessChain
+{
+    targets: [(FramebufferId, TextureId); 2],
+    width: i32,
+    height: i32,
+    //Effects run in this order, effects missing in it run after the others
+    order: std::vec::Vec<PostProcessEffects>,
+    disabled: std::collections::HashSet<PostProcessEffects>,
+}
+
+impl PostProcessChain
+{
+    //Effects that change the scene itself first, the monitor last
+    pub const DEFAULT_ORDER: [PostProcessEffects; 6] = [ PostProcessEffects::PIXELATE, PostProcessEffects::DESATURATE, PostProcessEffects::CHROMATIC_ABERRATION,
+        PostProcessEffects::VIGNETTE, PostProcessEffects::FLASH, PostProcessEffects::CRT ];
+
+    pub fn new(backend: &mut dyn RenderBackend, width: i32, height: i32, linear: bool) -> Result<PostProcessChain, String>
+    {
+        Ok(PostProcessChain {
+            targets: [ backend.create_render_target(width, height, linear)?, backend.create_render_target(width, height, linear)? ],
+            width,
+            height,
+            order: PostProcessChain::DEFAULT_ORDER.to_vec(),
+            disabled: std::collections::HashSet::new(),
+        })
+    }
+
+    //The targets have the size of the scene
+    pub fn resize(&mut self, backend: &mut dyn RenderBackend, width: i32, height: i32, linear: bool) -> Result<(), String>
+    {
+        for target in self.targets.iter_mut()
+        {
+            let new_target = backend.create_render_target(width, height, linear)?;
+            backend.delete_render_target(target.0, target.1);
+            *target = new_target;
+        }
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    pub fn set_texture_filter(&self, backend: &mut dyn RenderBackend, linear: bool)
+    {
+        for target in self.targets.iter()
+        {
+            backend.set_texture_filter(target.1, linear);
+        }
+    }
+
+    pub fn set_order(&mut self, order: std::vec::Vec<PostProcessEffects>)
+    {
+        self.order = order;
+    }
+
+    pub fn set_enabled(&mut self, effect_type: PostProcessEffects, enabled: bool)
+    {
+        if enabled
+        {
+            self.disabled.remove(&effect_type);
+        }
+        else
+        {
+            self.disabled.insert(effect_type);
+        }
+    }
+
+    pub fn is_enabled(&self, effect_type: PostProcessEffects) -> bool
+    {
+        !self.disabled.contains(&effect_type)
+    }
+
+    fn get_position(&self, effect_type: PostProcessEffects) -> usize
+    {
+        self.order.iter().position(|x| *x == effect_type).unwrap_or(self.order.len())
+    }
+
+    //Returns the texture with the result, the source itself when no effect is enabled
+    pub fn apply(&self, backend: &mut dyn RenderBackend, effects: &[Box<dyn Effect>], shaders: &std::collections::HashMap<PostProcessEffects, ProgramId>, source: TextureId) -> TextureId
+    {
+        let mut enabled_effects: std::vec::Vec<&Box<dyn Effect>> = effects.iter().filter(|x| self.is_enabled(x.get_effect_type())).collect();
+        //Stable, so effects of the same type keep the order they were started in
+        enabled_effects.sort_by_key(|x| self.get_position(x.get_effect_type()));
+
+        let mut result = source;
+        for (i, effect) in enabled_effects.iter().enumerate()
+        {
+            let (framebuffer, texture) = self.targets[i % 2];
+            backend.bind_render_target(Some(framebuffer));
+            backend.set_viewport(0, 0, self.width, self.height);
+            effect.apply(backend, result, shaders[&effect.get_effect_type()]);
+            result = texture;
+        }
+        result
+    }
+}
@@ -0,0 +1,57 @@
+use crate::view::post_process_effect;
+use crate::view::post_process_effect::effect::EffectState;
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId};
+
+//Red and blue are pulled apart towards the edges of the screen, like a cheap lens. The strength is how far, in parts of the distance to the center.
+pub struct ChromaticAberration
+{
+    state: EffectState,
+}
+
+impl ChromaticAberration
+{
+    pub fn new(running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> ChromaticAberration
+    {
+        ChromaticAberration { state: EffectState::new(PostProcessEffects::CHROMATIC_ABERRATION, running_time, max_running_time, parameters) }
+    }
+}
+
+impl post_process_effect::effect::Effect for ChromaticAberration
+{
+    fn get_state(&self) -> &EffectState
+    {
+        &self.state
+    }
+
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId)
+    {
+        backend.use_program(program);
+        backend.bind_texture(source);
+        self.upload_parameters(backend, program);
+
+        backend.draw_triangles(0, 6);
+    }
+}
+
+pub fn get_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D tex;
+        uniform float strength;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            vec2 offset = (uv - vec2(0.5)) * strength;
+            outColor = vec4(texture(tex, uv + offset).r, texture(tex, uv).g, texture(tex, uv - offset).b, 1.0);
+        }
+    "#;
+
+    backend.create_program(post_process_effect::VERTEX_SHADER, frag_shader, &[])
+}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use crate::view::post_process_effect;
+use crate::view::post_process_effect::effect::EffectState;
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId};
+
+//Scanlines and the bent glass of an old monitor. The radius is how much the screen is bent, the strength how dark every other row gets.
+pub struct Crt
+{
+    state: EffectState,
+}
+
+impl Crt
+{
+    pub fn new(running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> Crt
+    {
+        Crt { state: EffectState::new(PostProcessEffects::CRT, running_time, max_running_time, parameters) }
+    }
+}
+
+impl post_process_effect::effect::Effect for Crt
+{
+    fn get_state(&self) -> &EffectState
+    {
+        &self.state
+    }
+
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId)
+    {
+        backend.use_program(program);
+        backend.bind_texture(source);
+        self.upload_parameters(backend, program);
+
+        backend.draw_triangles(0, 6);
+    }
+}
+
+pub fn get_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D tex;
+        uniform float radius;
+        uniform float strength;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            //Pushes the corners outwards, the parts of the scene bent out of the screen are cut off
+            vec2 centered = uv * 2.0 - 1.0;
+            centered *= 1.0 + radius * centered.yx * centered.yx;
+            vec2 curvedUv = centered * 0.5 + 0.5;
+            if(any(lessThan(curvedUv, vec2(0.0))) || any(greaterThan(curvedUv, vec2(1.0))))
+            {
+                outColor = vec4(0.0, 0.0, 0.0, 1.0);
+                return;
+            }
+
+            //1 in the middle of even rows of the scene, 0 in the middle of odd ones
+            float scanline = 0.5 + 0.5 * sin(curvedUv.y * float(textureSize(tex, 0).y) * 3.14159265);
+            outColor = vec4(texture(tex, curvedUv).rgb * (1.0 - strength * (1.0 - scanline)), 1.0);
+        }
+    "#;
+
+    backend.create_program(post_process_effect::VERTEX_SHADER, frag_shader, &[])
+}
\ No newline at end of file
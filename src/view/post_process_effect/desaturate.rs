@@ -0,0 +1,59 @@
+use crate::view::post_process_effect;
+use crate::view::post_process_effect::effect::EffectState;
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId};
+
+//Takes the colors out of the scene, the grey is tinted with the color. The strength is how grey.
+pub struct Desaturate
+{
+    state: EffectState,
+}
+
+impl Desaturate
+{
+    pub fn new(running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> Desaturate
+    {
+        Desaturate { state: EffectState::new(PostProcessEffects::DESATURATE, running_time, max_running_time, parameters) }
+    }
+}
+
+impl post_process_effect::effect::Effect for Desaturate
+{
+    fn get_state(&self) -> &EffectState
+    {
+        &self.state
+    }
+
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId)
+    {
+        backend.use_program(program);
+        backend.bind_texture(source);
+        self.upload_parameters(backend, program);
+
+        backend.draw_triangles(0, 6);
+    }
+}
+
+pub fn get_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D tex;
+        uniform vec3 color;
+        uniform float strength;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            vec3 scene = texture(tex, uv).rgb;
+            float luminance = dot(scene, vec3(0.299, 0.587, 0.114));
+            outColor = vec4(mix(scene, luminance * color, strength), 1.0);
+        }
+    "#;
+
+    backend.create_program(post_process_effect::VERTEX_SHADER, frag_shader, &[])
+}
\ No newline at end of file
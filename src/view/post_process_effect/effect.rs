@@ -1,12 +1,58 @@
-use crate::view_models::PostProcessEffects;
-use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlTexture};
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId, UniformValue};
+
+//What every effect has in common, the effects themselves only add how they are drawn
+pub struct EffectState
+{
+    pub effect_type: PostProcessEffects,
+    pub running_time: f32,
+    pub max_running_time: f32,
+    pub parameters: PostProcessParameters,
+}
+
+impl EffectState
+{
+    pub fn new(effect_type: PostProcessEffects, running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> EffectState
+    {
+        EffectState { effect_type, running_time, max_running_time, parameters }
+    }
+}
 
 pub trait Effect
 {
-    fn get_effect_type(&self) -> PostProcessEffects;
-    fn set_running_time(&mut self, running_time: f32);
-    fn get_running_time(&self) -> f32;
-    fn set_max_running_time(&mut self, max_running_time: f32);
-    fn get_max_running_time(&self) -> f32;
-    fn apply(&self, context: &WebGl2RenderingContext, render_texture: &WebGlTexture, program: &WebGlProgram);
+    fn get_state(&self) -> &EffectState;
+
+    fn get_effect_type(&self) -> PostProcessEffects
+    {
+        self.get_state().effect_type
+    }
+
+    //From 0 when the effect starts to 1 when it ends
+    fn get_progress(&self) -> f32
+    {
+        let state = self.get_state();
+        if state.max_running_time > 0.0
+        {
+            (state.running_time / state.max_running_time).clamp(0.0, 1.0)
+        }
+        else
+        {
+            1.0
+        }
+    }
+
+    //Sets the color, radius and strength uniforms to their eased values. A shader without one of them optimizes it away,
+    //so missing uniforms are ignored.
+    fn upload_parameters(&self, backend: &mut dyn RenderBackend, program: ProgramId)
+    {
+        let progress = self.get_progress();
+        let parameters = &self.get_state().parameters;
+        backend.set_uniform(program, "color", UniformValue::Vec3(parameters.color.get(progress))).ok();
+        backend.set_uniform(program, "radius", UniformValue::Float(parameters.radius.get(progress))).ok();
+        backend.set_uniform(program, "strength", UniformValue::Float(parameters.strength.get(progress))).ok();
+    }
+
+    //Draws the whole screen into the bound render target, reading the result of the effects before from the source.
+    //The output is opaque, it replaces what was there before.
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId);
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use crate::view::post_process_effect;
+use crate::view::post_process_effect::effect::EffectState;
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId};
+
+pub struct Flash
+{
+    state: EffectState,
+}
+
+impl Flash
+{
+    pub fn new(running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> Flash
+    {
+        Flash { state: EffectState::new(PostProcessEffects::FLASH, running_time, max_running_time, parameters) }
+    }
+}
+
+impl post_process_effect::effect::Effect for Flash
+{
+    fn get_state(&self) -> &EffectState
+    {
+        &self.state
+    }
+
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId)
+    {
+        backend.use_program(program);
+        backend.bind_texture(source);
+        //The flash fades out by easing its strength to 0
+        self.upload_parameters(backend, program);
+
+        backend.draw_triangles(0, 6);
+    }
+}
+
+pub fn get_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D tex;
+        uniform vec3 color;
+        uniform float strength;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            outColor = vec4(mix(texture(tex, uv).rgb, color, strength), 1.0);
+        }
+    "#;
+
+    backend.create_program(post_process_effect::VERTEX_SHADER, frag_shader, &[])
+}
@@ -0,0 +1,62 @@
+use crate::view::post_process_effect;
+use crate::view::post_process_effect::effect::EffectState;
+use crate::view_models::{PostProcessEffects, PostProcessParameters};
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId};
+
+//The scene breaks into blocks that grow with the strength and dissolves into the color, e.g. as a transition.
+//The radius is the size of the biggest blocks in parts of the screen height.
+pub struct Pixelate
+{
+    state: EffectState,
+}
+
+impl Pixelate
+{
+    pub fn new(running_time: f32, max_running_time: f32, parameters: PostProcessParameters) -> Pixelate
+    {
+        Pixelate { state: EffectState::new(PostProcessEffects::PIXELATE, running_time, max_running_time, parameters) }
+    }
+}
+
+impl post_process_effect::effect::Effect for Pixelate
+{
+    fn get_state(&self) -> &EffectState
+    {
+        &self.state
+    }
+
+    fn apply(&self, backend: &mut dyn RenderBackend, source: TextureId, program: ProgramId)
+    {
+        backend.use_program(program);
+        backend.bind_texture(source);
+        self.upload_parameters(backend, program);
+
+        backend.draw_triangles(0, 6);
+    }
+}
+
+pub fn get_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D tex;
+        uniform vec3 color;
+        uniform float radius;
+        uniform float strength;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            vec2 size = vec2(textureSize(tex, 0));
+            float blockSize = max(1.0, strength * radius * size.y);
+            vec2 blockUv = (floor(uv * size / blockSize) + 0.5) * blockSize / size;
+            outColor = vec4(mix(texture(tex, blockUv).rgb, color, strength * strength), 1.0);
+        }
+    "#;
+
+    backend.create_program(post_process_effect::VERTEX_SHADER, frag_shader, &[])
+}
\ No newline at end of file
@@ -0,0 +1,204 @@
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId, VertexArrayId, BufferId, FramebufferId, UniformValue};
+
+use cgmath;
+
+//A call made to the RecordingBackend, shader sources and image data are left out to keep the recording readable
+#[derive(Clone, PartialEq, Debug)]
+pub enum RenderCommand
+{
+    CreateProgram { program: ProgramId },
+    CreateTexture { texture: TextureId, width: u32, height: u32, linear: bool },
+    CreateRenderTarget { framebuffer: FramebufferId, texture: TextureId, width: i32, height: i32, linear: bool },
+    DeleteRenderTarget { framebuffer: FramebufferId, texture: TextureId },
+    CreateDataTexture { texture: TextureId, width: u32, height: u32, data: std::vec::Vec<i32> },
+    UpdateDataTexture { texture: TextureId, x: u32, y: u32, width: u32, height: u32, data: std::vec::Vec<i32> },
+    DeleteTexture { texture: TextureId },
+    SetTextureFilter { texture: TextureId, linear: bool },
+    CreateQuad { vertex_array: VertexArrayId, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32> },
+    CreateInstancedQuad { vertex_array: VertexArrayId, buffer: BufferId, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>, instance_attributes: std::vec::Vec<(u32, i32)> },
+    UpdateInstanceBuffer { buffer: BufferId, data: std::vec::Vec<f32> },
+    SetUniform { program: ProgramId, name: String, value: UniformValue },
+    UseProgram { program: ProgramId },
+    BindVertexArray { vertex_array: VertexArrayId },
+    BindTexture { texture: TextureId },
+    BindTextureToUnit { texture: TextureId, unit: u32 },
+    BindRenderTarget { framebuffer: Option<FramebufferId> },
+    SetViewport { x: i32, y: i32, width: i32, height: i32 },
+    EnableAlphaBlending,
+    EnableMultiplyBlending,
+    Clear { color: [f32;4] },
+    DrawTriangles { first_vertex: i32, vertex_count: i32 },
+    DrawTrianglesInstanced { first_vertex: i32, vertex_count: i32, instance_count: i32 },
+}
+
+//Shared with whoever wants to look at the recording, since the backend itself is owned by the view
+pub type RenderLog = std::rc::Rc<std::cell::RefCell<std::vec::Vec<RenderCommand>>>;
+
+//Draws nothing, but logs every call, so tests can check what a frame would draw without a browser or a GPU
+pub struct RecordingBackend
+{
+    log: RenderLog,
+    next_id: u32,
+}
+
+impl RecordingBackend
+{
+    pub fn new() -> RecordingBackend
+    {
+        RecordingBackend { log: RenderLog::default(), next_id: 1 }
+    }
+
+    pub fn get_log(&self) -> RenderLog
+    {
+        self.log.clone()
+    }
+
+    fn create_id(&mut self) -> u32
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Default for RecordingBackend
+{
+    fn default() -> RecordingBackend
+    {
+        RecordingBackend::new()
+    }
+}
+
+impl RenderBackend for RecordingBackend
+{
+    fn create_program(&mut self, _vertex_source: &str, _fragment_source: &str, _attributes: &[(u32, &str)]) -> Result<ProgramId, String>
+    {
+        let program = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateProgram { program });
+        Ok(program)
+    }
+
+    fn create_texture(&mut self, image: image::RgbaImage, linear: bool) -> Result<TextureId, String>
+    {
+        let texture = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateTexture { texture, width: image.width(), height: image.height(), linear });
+        Ok(texture)
+    }
+
+    fn create_render_target(&mut self, width: i32, height: i32, linear: bool) -> Result<(FramebufferId, TextureId), String>
+    {
+        let framebuffer = self.create_id();
+        let texture = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateRenderTarget { framebuffer, texture, width, height, linear });
+        Ok((framebuffer, texture))
+    }
+
+    fn delete_render_target(&mut self, framebuffer: FramebufferId, texture: TextureId)
+    {
+        self.log.borrow_mut().push(RenderCommand::DeleteRenderTarget { framebuffer, texture });
+    }
+
+    fn create_data_texture(&mut self, width: u32, height: u32, data: &[i32]) -> Result<TextureId, String>
+    {
+        let texture = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateDataTexture { texture, width, height, data: data.to_vec() });
+        Ok(texture)
+    }
+
+    fn update_data_texture(&mut self, texture: TextureId, x: u32, y: u32, width: u32, height: u32, data: &[i32]) -> Result<(), String>
+    {
+        self.log.borrow_mut().push(RenderCommand::UpdateDataTexture { texture, x, y, width, height, data: data.to_vec() });
+        Ok(())
+    }
+
+    fn delete_texture(&mut self, texture: TextureId)
+    {
+        self.log.borrow_mut().push(RenderCommand::DeleteTexture { texture });
+    }
+
+    fn set_texture_filter(&mut self, texture: TextureId, linear: bool)
+    {
+        self.log.borrow_mut().push(RenderCommand::SetTextureFilter { texture, linear });
+    }
+
+    fn create_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>) -> Result<(i32, VertexArrayId), String>
+    {
+        let vertex_array = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateQuad { vertex_array, center, size });
+        Ok((6, vertex_array))
+    }
+
+    fn create_instanced_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>, instance_attributes: &[(u32, i32)]) -> Result<(i32, VertexArrayId, BufferId), String>
+    {
+        let vertex_array = self.create_id();
+        let buffer = self.create_id();
+        self.log.borrow_mut().push(RenderCommand::CreateInstancedQuad { vertex_array, buffer, center, size, instance_attributes: instance_attributes.to_vec() });
+        Ok((6, vertex_array, buffer))
+    }
+
+    fn update_instance_buffer(&mut self, buffer: BufferId, data: &[f32])
+    {
+        self.log.borrow_mut().push(RenderCommand::UpdateInstanceBuffer { buffer, data: data.to_vec() });
+    }
+
+    fn set_uniform(&mut self, program: ProgramId, name: &str, value: UniformValue) -> Result<(), String>
+    {
+        self.log.borrow_mut().push(RenderCommand::SetUniform { program, name: name.to_string(), value });
+        Ok(())
+    }
+
+    fn use_program(&mut self, program: ProgramId)
+    {
+        self.log.borrow_mut().push(RenderCommand::UseProgram { program });
+    }
+
+    fn bind_vertex_array(&mut self, vertex_array: VertexArrayId)
+    {
+        self.log.borrow_mut().push(RenderCommand::BindVertexArray { vertex_array });
+    }
+
+    fn bind_texture(&mut self, texture: TextureId)
+    {
+        self.log.borrow_mut().push(RenderCommand::BindTexture { texture });
+    }
+
+    fn bind_texture_to_unit(&mut self, texture: TextureId, unit: u32)
+    {
+        self.log.borrow_mut().push(RenderCommand::BindTextureToUnit { texture, unit });
+    }
+
+    fn bind_render_target(&mut self, framebuffer: Option<FramebufferId>)
+    {
+        self.log.borrow_mut().push(RenderCommand::BindRenderTarget { framebuffer });
+    }
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32)
+    {
+        self.log.borrow_mut().push(RenderCommand::SetViewport { x, y, width, height });
+    }
+
+    fn enable_alpha_blending(&mut self)
+    {
+        self.log.borrow_mut().push(RenderCommand::EnableAlphaBlending);
+    }
+
+    fn enable_multiply_blending(&mut self)
+    {
+        self.log.borrow_mut().push(RenderCommand::EnableMultiplyBlending);
+    }
+
+    fn clear(&mut self, color: [f32;4])
+    {
+        self.log.borrow_mut().push(RenderCommand::Clear { color });
+    }
+
+    fn draw_triangles(&mut self, first_vertex: i32, vertex_count: i32)
+    {
+        self.log.borrow_mut().push(RenderCommand::DrawTriangles { first_vertex, vertex_count });
+    }
+
+    fn draw_triangles_instanced(&mut self, first_vertex: i32, vertex_count: i32, instance_count: i32)
+    {
+        self.log.borrow_mut().push(RenderCommand::DrawTrianglesInstanced { first_vertex, vertex_count, instance_count });
+    }
+}
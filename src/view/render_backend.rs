@@ -0,0 +1,67 @@
+//Everything the view needs from the graphics API. The view only talks to this trait, so it can draw with WebGL2 in the browser
+//or into a recording for tests (see RecordingBackend).
+//
+//Programs, textures, vertex arrays and buffers are handed out as ids, the backend keeps the actual objects.
+
+use cgmath;
+
+pub type ProgramId = u32;
+pub type TextureId = u32;
+pub type VertexArrayId = u32;
+pub type BufferId = u32;
+pub type FramebufferId = u32;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum UniformValue
+{
+    Float(f32),
+    Int(i32),
+    Vec2(cgmath::Vector2<f32>),
+    Vec3(cgmath::Vector3<f32>),
+    FloatArray(std::vec::Vec<f32>),
+    Vec2Array(std::vec::Vec<cgmath::Vector2<f32>>),
+    Vec4Array(std::vec::Vec<cgmath::Vector4<f32>>),
+    IntArray(std::vec::Vec<i32>),
+}
+
+pub trait RenderBackend
+{
+    //Attributes are bound to the given locations before linking
+    fn create_program(&mut self, vertex_source: &str, fragment_source: &str, attributes: &[(u32, &str)]) -> Result<ProgramId, String>;
+    fn create_texture(&mut self, image: image::RgbaImage, linear: bool) -> Result<TextureId, String>;
+    //Empty texture with a framebuffer to draw into it, e.g. to render the scene at a lower resolution than the canvas
+    fn create_render_target(&mut self, width: i32, height: i32, linear: bool) -> Result<(FramebufferId, TextureId), String>;
+    //Deletes the framebuffer together with its texture
+    fn delete_render_target(&mut self, framebuffer: FramebufferId, texture: TextureId);
+    //Single channel integer texture for data like the map of a level, read with texelFetch. The data is given row by row.
+    fn create_data_texture(&mut self, width: u32, height: u32, data: &[i32]) -> Result<TextureId, String>;
+    //Replaces the given rectangle of a data texture, so single changes do not upload everything
+    fn update_data_texture(&mut self, texture: TextureId, x: u32, y: u32, width: u32, height: u32, data: &[i32]) -> Result<(), String>;
+    fn delete_texture(&mut self, texture: TextureId);
+    fn set_texture_filter(&mut self, texture: TextureId, linear: bool);
+    //Quad with positions at location 0 and uvs at location 1, returns the number of vertices
+    fn create_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>) -> Result<(i32, VertexArrayId), String>;
+    //Same quad plus a buffer with one entry per instance. The instance attributes are given as (location, number of floats),
+    //their data is interleaved in the buffer in that order.
+    fn create_instanced_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>, instance_attributes: &[(u32, i32)]) -> Result<(i32, VertexArrayId, BufferId), String>;
+    fn update_instance_buffer(&mut self, buffer: BufferId, data: &[f32]);
+
+    fn set_uniform(&mut self, program: ProgramId, name: &str, value: UniformValue) -> Result<(), String>;
+    fn use_program(&mut self, program: ProgramId);
+    fn bind_vertex_array(&mut self, vertex_array: VertexArrayId);
+    fn bind_texture(&mut self, texture: TextureId);
+    //For shaders with more than one texture, the sampler uniform has to be set to the unit
+    fn bind_texture_to_unit(&mut self, texture: TextureId, unit: u32);
+    //Everything drawn afterwards goes into the render target, None draws to the canvas
+    fn bind_render_target(&mut self, framebuffer: Option<FramebufferId>);
+    //Area of the canvas that is drawn to, in pixels from the bottom left
+    fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32);
+    //Blends with the source alpha
+    fn enable_alpha_blending(&mut self);
+    //Multiplies what is drawn with what is already there, e.g. light over the scene
+    fn enable_multiply_blending(&mut self);
+    fn clear(&mut self, color: [f32;4]);
+    fn draw_triangles(&mut self, first_vertex: i32, vertex_count: i32);
+    //Draws the vertices once per instance, with the instance attributes of the bound vertex array
+    fn draw_triangles_instanced(&mut self, first_vertex: i32, vertex_count: i32, instance_count: i32);
+}
@@ -0,0 +1,55 @@
+//How the game is fit into a canvas whose aspect ratio differs from the one of the level.
+//The level is drawn into the viewport, everything outside of it stays black.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ScaleMode
+{
+    //As big as possible while the whole level stays visible, with black bars on two sides
+    Letterbox = 0,
+    //Like letterbox, but only whole multiples of the base resolution, so pixel art stays crisp
+    IntegerScale = 1,
+    //Covers the whole canvas, the parts of the level that do not fit are cut off
+    Fill = 2,
+}
+
+//Area of the canvas in pixels, x and y from the bottom left like glViewport. Fill may start outside of the canvas.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Viewport
+{
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ScaleMode
+{
+    pub fn from(mode_code: u8) -> Result<ScaleMode, String>
+    {
+        match mode_code
+        {
+            0 => Ok(ScaleMode::Letterbox),
+            1 => Ok(ScaleMode::IntegerScale),
+            2 => Ok(ScaleMode::Fill),
+            _ => Err(format!("Scale mode {} not found", mode_code)),
+        }
+    }
+
+    //The base size is the resolution the level looks right at, its aspect ratio is kept in every mode
+    pub fn get_viewport(&self, canvas_width: i32, canvas_height: i32, base_width: i32, base_height: i32) -> Viewport
+    {
+        let scale_x = canvas_width as f32 / base_width as f32;
+        let scale_y = canvas_height as f32 / base_height as f32;
+
+        let scale = match self
+        {
+            ScaleMode::Letterbox => scale_x.min(scale_y),
+            //Smaller than the base resolution there is no whole multiple left, so it falls back to letterbox
+            ScaleMode::IntegerScale => if scale_x.min(scale_y) >= 1.0 { scale_x.min(scale_y).floor() } else { scale_x.min(scale_y) },
+            ScaleMode::Fill => scale_x.max(scale_y),
+        };
+
+        let width = (base_width as f32 * scale).round() as i32;
+        let height = (base_height as f32 * scale).round() as i32;
+        Viewport { x: (canvas_width - width) / 2, y: (canvas_height - height) / 2, width, height }
+    }
+}
@@ -1,4 +1,4 @@
-use web_sys::{WebGlProgram, WebGl2RenderingContext, WebGlShader, WebGlTexture, WebGlVertexArrayObject};
+use web_sys::{WebGlProgram, WebGl2RenderingContext, WebGlShader, WebGlTexture, WebGlVertexArrayObject, WebGlBuffer, WebGlFramebuffer};
 use cgmath;
 
 pub fn compile_shader(context: &WebGl2RenderingContext, shader_type: u32, source: &str) -> Result<WebGlShader, String> 
@@ -70,6 +70,14 @@ pub fn set_uniform1f(context: &WebGl2RenderingContext, program: &WebGlProgram, d
     Ok(())
 }
 
+pub fn set_uniform1i(context: &WebGl2RenderingContext, program: &WebGlProgram, data: i32, name: &str) -> Result<(), String>
+{
+    context.use_program(Some(program));
+    let loc = context.get_uniform_location(program, name).ok_or(format!("Failed to get location of {}", name))?;
+    context.uniform1i(Some(&loc), data);
+    Ok(())
+}
+
 pub fn set_uniform2f(context: &WebGl2RenderingContext, program: &WebGlProgram, data: cgmath::Vector2<f32>, name: &str) -> Result<(), String>
 {
     context.use_program(Some(program));
@@ -78,23 +86,31 @@ pub fn set_uniform2f(context: &WebGl2RenderingContext, program: &WebGlProgram, d
     Ok(())
 }
 
-pub fn set_uniform1i_arr10(context: &WebGl2RenderingContext, shader: &WebGlProgram, data: [i32;10], uniform_name: &str) -> Result<(), String>
+pub fn set_uniform3f(context: &WebGl2RenderingContext, program: &WebGlProgram, data: cgmath::Vector3<f32>, name: &str) -> Result<(), String>
+{
+    context.use_program(Some(program));
+    let loc = context.get_uniform_location(program, name).ok_or(format!("Failed to get location of {}", name))?;
+    context.uniform3f(Some(&loc), data.x, data.y, data.z);
+    Ok(())
+}
+
+pub fn set_uniform1f_arr(context: &WebGl2RenderingContext, shader: &WebGlProgram, data: &[f32], uniform_name: &str) -> Result<(), String>
 {
     context.use_program(Some(shader));
     let loc = context.get_uniform_location(shader, uniform_name).ok_or(format!("Failed to get location of {}", uniform_name))?;
-    context.uniform1iv_with_i32_array(Some(&loc), &data.to_vec());
+    context.uniform1fv_with_f32_array(Some(&loc), data);
     Ok(())
 }
 
-pub fn set_uniform1f_arr10(context: &WebGl2RenderingContext, shader: &WebGlProgram, data: [f32;10], uniform_name: &str) -> Result<(), String>
+pub fn set_uniform1i_arr(context: &WebGl2RenderingContext, shader: &WebGlProgram, data: &[i32], uniform_name: &str) -> Result<(), String>
 {
     context.use_program(Some(shader));
     let loc = context.get_uniform_location(shader, uniform_name).ok_or(format!("Failed to get location of {}", uniform_name))?;
-    context.uniform1fv_with_f32_array(Some(&loc), &data.to_vec());
+    context.uniform1iv_with_i32_array(Some(&loc), data);
     Ok(())
 }
 
-pub fn set_uniform2f_arr10(context: &WebGl2RenderingContext, shader: &WebGlProgram, vec2_data: [cgmath::Vector2<f32>;10], uniform_name: &str) -> Result<(), String>
+pub fn set_uniform2f_arr(context: &WebGl2RenderingContext, shader: &WebGlProgram, vec2_data: &[cgmath::Vector2<f32>], uniform_name: &str) -> Result<(), String>
 {
     context.use_program(Some(shader));
     let loc = context.get_uniform_location(shader, uniform_name).ok_or(format!("Failed to get location of {}", uniform_name))?;
@@ -109,25 +125,32 @@ pub fn set_uniform2f_arr10(context: &WebGl2RenderingContext, shader: &WebGlProgr
     Ok(())
 }
 
-pub fn initialize_texture(context: &WebGl2RenderingContext, texture: image::RgbaImage, program: &WebGlProgram, linear: bool) -> Result<WebGlTexture, String>
+pub fn set_uniform4f_arr(context: &WebGl2RenderingContext, shader: &WebGlProgram, vec4_data: &[cgmath::Vector4<f32>], uniform_name: &str) -> Result<(), String>
+{
+    context.use_program(Some(shader));
+    let loc = context.get_uniform_location(shader, uniform_name).ok_or(format!("Failed to get location of {}", uniform_name))?;
+    let mut data : std::vec::Vec<f32> = std::vec::Vec::new();
+    for x in vec4_data.iter()
+    {
+        data.push(x.x);
+        data.push(x.y);
+        data.push(x.z);
+        data.push(x.w);
+    }
+
+    context.uniform4fv_with_f32_array(Some(&loc), &data);
+    Ok(())
+}
+
+pub fn initialize_texture(context: &WebGl2RenderingContext, texture: image::RgbaImage, linear: bool) -> Result<WebGlTexture, String>
 {
-    context.use_program(Some(program));
     let tex = context.create_texture().ok_or("failed to create texture")?;
 
     context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&tex));
     context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_S, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
     context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_T, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
-    if linear
-    {
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MIN_FILTER, WebGl2RenderingContext::LINEAR as i32);
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MAG_FILTER, WebGl2RenderingContext::LINEAR as i32);
-    }
-    else
-    {
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MIN_FILTER, WebGl2RenderingContext::NEAREST as i32);
-        context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MAG_FILTER, WebGl2RenderingContext::NEAREST as i32);
-    }
-    context.generate_mipmap(WebGl2RenderingContext::TEXTURE_2D);        
+    //No mipmaps, the tile maps are atlases and the shaders jump between tiles, which would pick the wrong mip level at tile borders
+    set_texture_filter(context, &tex, linear);
 
     unsafe 
     {
@@ -156,10 +179,122 @@ pub fn initialize_texture(context: &WebGl2RenderingContext, texture: image::Rgba
     Ok(tex)
 }
 
+//Leaves the texture bound
+pub fn set_texture_filter(context: &WebGl2RenderingContext, texture: &WebGlTexture, linear: bool)
+{
+    let filter = if linear { WebGl2RenderingContext::LINEAR } else { WebGl2RenderingContext::NEAREST };
 
-pub fn initialize_quad_with_uvs(context: &WebGl2RenderingContext, program: &WebGlProgram, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>) -> Result<(usize, WebGlVertexArrayObject), String> 
+    context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(texture));
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MIN_FILTER, filter as i32);
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MAG_FILTER, filter as i32);
+}
+
+//Integer textures can not be filtered, so they are always sampled nearest
+pub fn initialize_data_texture(context: &WebGl2RenderingContext, width: i32, height: i32, data: &[i32]) -> Result<WebGlTexture, String>
+{
+    let tex = context.create_texture().ok_or("failed to create data texture")?;
+
+    context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&tex));
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_S, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_T, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MIN_FILTER, WebGl2RenderingContext::NEAREST as i32);
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_MAG_FILTER, WebGl2RenderingContext::NEAREST as i32);
+
+    //No allocations while the view exists, see initialize_quad_with_uvs
+    unsafe
+    {
+        let data_array = js_sys::Int32Array::view(data);
+
+        match context.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                WebGl2RenderingContext::TEXTURE_2D,
+                0,
+                WebGl2RenderingContext::R32I as i32,
+                width,
+                height,
+                0,
+                WebGl2RenderingContext::RED_INTEGER,
+                WebGl2RenderingContext::INT,
+                Some(&data_array)
+            )
+        {
+            Ok(x) => Ok(x),
+            Err(_) => Err("failed to copy data to data texture"),
+        }?;
+    }
+
+    Ok(tex)
+}
+
+pub fn update_data_texture(context: &WebGl2RenderingContext, texture: &WebGlTexture, x: i32, y: i32, width: i32, height: i32, data: &[i32]) -> Result<(), String>
+{
+    context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(texture));
+
+    unsafe
+    {
+        let data_array = js_sys::Int32Array::view(data);
+
+        match context.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                WebGl2RenderingContext::TEXTURE_2D,
+                0,
+                x,
+                y,
+                width,
+                height,
+                WebGl2RenderingContext::RED_INTEGER,
+                WebGl2RenderingContext::INT,
+                Some(&data_array)
+            )
+        {
+            Ok(x) => Ok(x),
+            Err(_) => Err("failed to update data texture"),
+        }?;
+    }
+
+    Ok(())
+}
+
+//Leaves the canvas bound, the view binds the framebuffer when it draws into it
+pub fn initialize_render_target(context: &WebGl2RenderingContext, width: i32, height: i32, linear: bool) -> Result<(WebGlFramebuffer, WebGlTexture), String>
+{
+    let tex = context.create_texture().ok_or("failed to create texture")?;
+
+    context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&tex));
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_S, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
+    context.tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, WebGl2RenderingContext::TEXTURE_WRAP_T, WebGl2RenderingContext::CLAMP_TO_EDGE as i32);
+    set_texture_filter(context, &tex, linear);
+
+    match context.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+        WebGl2RenderingContext::TEXTURE_2D,
+        0,
+        WebGl2RenderingContext::RGBA as i32,
+        width,
+        height,
+        0,
+        WebGl2RenderingContext::RGBA,
+        WebGl2RenderingContext::UNSIGNED_BYTE,
+        None
+        )
+    {
+        Ok(x) => Ok(x),
+        Err(_) => Err("failed to initialize render target texture"),
+    }?;
+
+    let framebuffer = context.create_framebuffer().ok_or("failed to create framebuffer")?;
+    context.bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, Some(&framebuffer));
+    context.framebuffer_texture_2d(WebGl2RenderingContext::FRAMEBUFFER, WebGl2RenderingContext::COLOR_ATTACHMENT0, WebGl2RenderingContext::TEXTURE_2D, Some(&tex), 0);
+    let status = context.check_framebuffer_status(WebGl2RenderingContext::FRAMEBUFFER);
+    context.bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, None);
+
+    if status != WebGl2RenderingContext::FRAMEBUFFER_COMPLETE
+    {
+        return Err(format!("Render target of {}x{} pixels is incomplete: {}", width, height, status));
+    }
+
+    Ok((framebuffer, tex))
+}
+
+pub fn initialize_quad_with_uvs(context: &WebGl2RenderingContext, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>) -> Result<(usize, WebGlVertexArrayObject), String> 
 {
-    context.use_program(Some(program));
     //Screen filling quad
     let right = center.x + size.x / 2.0;
     let left = center.x - size.x / 2.0;
@@ -231,4 +366,43 @@ pub fn initialize_quad_with_uvs(context: &WebGl2RenderingContext, program: &WebG
     context.enable_vertex_attrib_array(1);
 
     Ok((vertices.len() / 3, vao))
-}
\ No newline at end of file
+}
+
+//Adds a buffer with one entry per instance to the vertex array, the attributes are (location, number of floats) and interleaved
+pub fn initialize_instance_buffer(context: &WebGl2RenderingContext, vao: &WebGlVertexArrayObject, attributes: &[(u32, i32)]) -> Result<WebGlBuffer, String>
+{
+    let buffer = context.create_buffer().ok_or("failed to create instance buffer")?;
+
+    context.bind_vertex_array(Some(vao));
+    context.bind_buffer(WebGl2RenderingContext::ARRAY_BUFFER, Some(&buffer));
+
+    let float_size = std::mem::size_of::<f32>() as i32;
+    let stride: i32 = attributes.iter().map(|x| x.1).sum::<i32>() * float_size;
+    let mut offset = 0;
+    for (location, size) in attributes.iter()
+    {
+        context.vertex_attrib_pointer_with_i32(*location, *size, WebGl2RenderingContext::FLOAT, false, stride, offset);
+        context.vertex_attrib_divisor(*location, 1);
+        context.enable_vertex_attrib_array(*location);
+        offset += size * float_size;
+    }
+
+    Ok(buffer)
+}
+
+pub fn update_buffer(context: &WebGl2RenderingContext, buffer: &WebGlBuffer, data: &[f32])
+{
+    context.bind_buffer(WebGl2RenderingContext::ARRAY_BUFFER, Some(buffer));
+
+    //No allocations while the view exists, see initialize_quad_with_uvs
+    unsafe
+    {
+        let data_array = js_sys::Float32Array::view(data);
+
+        context.buffer_data_with_array_buffer_view(
+            WebGl2RenderingContext::ARRAY_BUFFER,
+            &data_array,
+            WebGl2RenderingContext::DYNAMIC_DRAW,
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//Draws the view models on the CPU, e.g. for golden image tests on machines without a GPU or for level thumbnails.
+//It follows the shaders of the WebGL2 view: positions are in clip space, the background uses the tile mirroring of background_helper,
+//sprites are looked up in the sprite atlas and everything is blended with the source alpha. Textures are sampled bilinear and clamped to the edge.
+
+use crate::sprite_atlas::SpriteAtlas;
+use crate::view::View;
+use crate::view::post_process_effect::chain::PostProcessChain;
+use crate::view_models::{LevelViewModel, SpritesViewModel, ParticlesViewModel, LightsViewModel, PostProcessViewModel, CameraViewModel, PostProcessEffect, PostProcessEffects};
+
+use cgmath;
+use cgmath::InnerSpace;
+
+type Color = [f32;4];
+
+pub struct SoftwareRenderer
+{
+    tile_map: image::RgbaImage,
+    tile_grid: (u32, u32),
+    sprite_tile_map: image::RgbaImage,
+    sprite_atlas: SpriteAtlas,
+    width: u32,
+    height: u32,
+}
+
+impl SoftwareRenderer
+{
+    pub fn new(tile_map: image::RgbaImage, tile_atlas: &SpriteAtlas, sprite_tile_map: image::RgbaImage, sprite_atlas: SpriteAtlas, width: u32, height: u32) -> Result<SoftwareRenderer, String>
+    {
+        let tile_grid = View::get_tile_grid(&tile_map, tile_atlas)?;
+        sprite_atlas.check_image_size(sprite_tile_map.width(), sprite_tile_map.height())?;
+
+        Ok(SoftwareRenderer { tile_map, tile_grid, sprite_tile_map, sprite_atlas, width, height })
+    }
+
+    //A level in daylight
+    pub fn render(&self, level: &LevelViewModel, sprites: &SpritesViewModel, particles: &ParticlesViewModel, post_process_effects: &PostProcessViewModel, camera: &CameraViewModel) -> Result<image::RgbaImage, String>
+    {
+        self.render_with_lights(level, sprites, particles, None, post_process_effects, camera)
+    }
+
+    pub fn render_with_lights(&self, level: &LevelViewModel, sprites: &SpritesViewModel, particles: &ParticlesViewModel, lights: Option<&LightsViewModel>, post_process_effects: &PostProcessViewModel, camera: &CameraViewModel) -> Result<image::RgbaImage, String>
+    {
+        let mut target: std::vec::Vec<Color> = vec![ [0.0, 0.0, 0.0, 1.0]; (self.width * self.height) as usize ];
+
+        self.render_background(&mut target, level, camera)?;
+        self.render_sprites(&mut target, sprites, camera)?;
+        self.render_particles(&mut target, particles, camera);
+        if let Some(lights) = lights
+        {
+            self.render_lighting(&mut target, level, lights, camera);
+        }
+        self.apply_post_process_effects(&mut target, post_process_effects);
+
+        let mut image = image::RgbaImage::new(self.width, self.height);
+        for (pixel, color) in image.pixels_mut().zip(target.iter())
+        {
+            *pixel = image::Rgba([ to_byte(color[0]), to_byte(color[1]), to_byte(color[2]), to_byte(color[3]) ]);
+        }
+        Ok(image)
+    }
+
+    fn render_background(&self, target: &mut [Color], level: &LevelViewModel, camera: &CameraViewModel) -> Result<(), String>
+    {
+        let tile_map_width = self.tile_grid.0 as f32;
+        let tile_map_height = self.tile_grid.1 as f32;
+
+        for y in 0..self.height
+        {
+            for x in 0..self.width
+            {
+                //Position in the level as seen by the camera, turned into a uv with the origin in the top left like the shader after flipping
+                let clip = self.to_clip_space(x, y);
+                let world = rotate(clip, -camera.rotation) / camera.zoom + camera.position;
+                let mut uv = cgmath::Vector2 { x: (world.x + 1.0) / 2.0, y: 1.0 - (world.y + 1.0) / 2.0 };
+
+                let col = (level.width * uv.x).floor();
+                let row = (level.height * uv.y).floor();
+                //Cells around the map, e.g. when the camera is zoomed out or shaken, are tile 0 like texelFetch outside the map texture
+                let tile_to_use = if col < 0.0 || row < 0.0 || col >= level.width || row >= level.height
+                {
+                    0.0
+                }
+                else
+                {
+                    let map_tile = (row * level.width + col) as usize;
+                    *level.data.get(map_tile).ok_or(format!("Map has no tile {}", map_tile))? as f32
+                };
+
+                //Every other tile is mirrored, so neighbouring tiles fit together
+                if glsl_mod(col, 2.0) >= 0.5
+                {
+                    uv.x = 1.0 - uv.x;
+                }
+                if glsl_mod(row, 2.0) >= 0.5
+                {
+                    uv.y = 1.0 - uv.y;
+                }
+
+                let tile_to_use_col = glsl_mod(tile_to_use, tile_map_width);
+                let tile_to_use_row = (tile_to_use / tile_map_width).floor();
+
+                let start_tile_x = tile_to_use_col / tile_map_width;
+                let map_tile_size_x = 1.0 / level.width;
+                let tiled_uv_x = mix(start_tile_x, start_tile_x + 1.0 / tile_map_width, glsl_mod(uv.x, map_tile_size_x) / map_tile_size_x);
+
+                let start_tile_y = tile_to_use_row / tile_map_height;
+                let map_tile_size_y = 1.0 / level.height;
+                let tiled_uv_y = mix(start_tile_y, start_tile_y + 1.0 / tile_map_height, glsl_mod(uv.y, map_tile_size_y) / map_tile_size_y);
+
+                let color = sample(&self.tile_map, tiled_uv_x, tiled_uv_y);
+                blend(&mut target[(y * self.width + x) as usize], color);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_sprites(&self, target: &mut [Color], sprites: &SpritesViewModel, camera: &CameraViewModel) -> Result<(), String>
+    {
+        for i in 0..sprites.get_count()
+        {
+            let rect = self.sprite_atlas.get_uv_rect(sprites.frames[i])?;
+            let alpha = sprites.alphas[i];
+
+            self.fill_quad(target, to_screen(sprites.positions[i], camera), sprites.sizes[i] / 2.0 * camera.zoom, camera.rotation, |uv| {
+                //The top of the sprite is at the top of the frame in the atlas
+                let mut color = sample(&self.sprite_tile_map, mix(rect.min_x, rect.max_x, uv.x), mix(rect.min_y, rect.max_y, 1.0 - uv.y));
+                color[3] *= alpha;
+                color
+            });
+        }
+
+        Ok(())
+    }
+
+    fn render_particles(&self, target: &mut [Color], particles: &ParticlesViewModel, camera: &CameraViewModel)
+    {
+        for i in 0..particles.get_count()
+        {
+            //Same as the particle shader, the quad covers the circle the particles spread in
+            let max_size = particles.max_speeds[i] * particles.running_times[i] * std::f32::consts::SQRT_2 * camera.zoom;
+            let color = [ 1.0, 0.0, 0.0, 1.0 - particles.running_times[i] / particles.max_running_times[i] ];
+
+            self.fill_quad(target, to_screen(particles.positions[i], camera), cgmath::Vector2 { x: max_size, y: max_size }, camera.rotation, |_| color);
+        }
+    }
+
+    //Multiplies the light of every pixel over the scene like the lighting shader, with the same steps towards the lights
+    fn render_lighting(&self, target: &mut [Color], level: &LevelViewModel, lights: &LightsViewModel, camera: &CameraViewModel)
+    {
+        //In tiles from the top left corner of the map
+        let to_tiles = |world: cgmath::Vector2<f32>| cgmath::Vector2 { x: (world.x + 1.0) / 2.0 * level.width, y: (1.0 - (world.y + 1.0) / 2.0) * level.height };
+        let light_positions: std::vec::Vec<cgmath::Vector2<f32>> = lights.positions.iter().map(|x| to_tiles(*x)).collect();
+
+        for y in 0..self.height
+        {
+            for x in 0..self.width
+            {
+                let clip = self.to_clip_space(x, y);
+                let pixel = to_tiles(rotate(clip, -camera.rotation) / camera.zoom + camera.position);
+                let mut light = lights.ambient;
+
+                for (i, light_position) in light_positions.iter().enumerate()
+                {
+                    let falloff = (1.0 - (pixel - light_position).magnitude() / lights.radii[i]).max(0.0);
+                    if falloff > 0.0 && is_visible(level, &lights.occluding_tiles, pixel, *light_position)
+                    {
+                        light += lights.colors[i] * lights.intensities[i] * falloff * falloff;
+                    }
+                }
+
+                let color = &mut target[(y * self.width + x) as usize];
+                color[0] *= light.x.min(1.0);
+                color[1] *= light.y.min(1.0);
+                color[2] *= light.z.min(1.0);
+            }
+        }
+    }
+
+    fn apply_post_process_effects(&self, target: &mut [Color], post_process_effects: &PostProcessViewModel)
+    {
+        //In the order of the WebGL2 view, as long as it is not changed there
+        let mut effects: std::vec::Vec<&PostProcessEffect> = post_process_effects.effects.iter().collect();
+        effects.sort_by_key(|x| PostProcessChain::DEFAULT_ORDER.iter().position(|y| *y == x.name));
+
+        for effect in effects
+        {
+            let progress = effect.get_progress();
+            let color = effect.parameters.color.get(progress);
+            let radius = effect.parameters.radius.get(progress);
+            let strength = effect.parameters.strength.get(progress);
+
+            //The shaders mix the scene with the color, which is the same as blending the color on top with the amount as alpha
+            match effect.name
+            {
+                PostProcessEffects::VIGNETTE => self.fill_quad(target, cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 1.0, y: 1.0 }, 0.0, |uv| {
+                    let distance = ((uv.x - 0.5) * (uv.x - 0.5) + (uv.y - 0.5) * (uv.y - 0.5)).sqrt();
+                    [ color.x, color.y, color.z, strength * smoothstep(radius, radius + 0.2, distance) ]
+                }),
+                PostProcessEffects::FLASH => self.fill_quad(target, cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 1.0, y: 1.0 }, 0.0, |_| [ color.x, color.y, color.z, strength ]),
+                PostProcessEffects::CRT => self.filter_screen(target, |uv, scene| {
+                    let centered = uv * 2.0 - cgmath::Vector2 { x: 1.0, y: 1.0 };
+                    let curved = cgmath::Vector2 { x: centered.x * (1.0 + radius * centered.y * centered.y), y: centered.y * (1.0 + radius * centered.x * centered.x) };
+                    let curved_uv = (curved + cgmath::Vector2 { x: 1.0, y: 1.0 }) * 0.5;
+                    if curved_uv.x < 0.0 || curved_uv.x > 1.0 || curved_uv.y < 0.0 || curved_uv.y > 1.0
+                    {
+                        return [ 0.0, 0.0, 0.0 ];
+                    }
+
+                    let scanline = 0.5 + 0.5 * (curved_uv.y * self.height as f32 * std::f32::consts::PI).sin();
+                    let color = scene(curved_uv);
+                    let brightness = 1.0 - strength * (1.0 - scanline);
+                    [ color[0] * brightness, color[1] * brightness, color[2] * brightness ]
+                }),
+                PostProcessEffects::CHROMATIC_ABERRATION => self.filter_screen(target, |uv, scene| {
+                    let offset = (uv - cgmath::Vector2 { x: 0.5, y: 0.5 }) * strength;
+                    [ scene(uv + offset)[0], scene(uv)[1], scene(uv - offset)[2] ]
+                }),
+                PostProcessEffects::PIXELATE => self.filter_screen(target, |uv, scene| {
+                    let block_size = (strength * radius * self.height as f32).max(1.0);
+                    let to_block = |uv: f32, size: f32| ((uv * size / block_size).floor() + 0.5) * block_size / size;
+                    let scene_color = scene(cgmath::Vector2 { x: to_block(uv.x, self.width as f32), y: to_block(uv.y, self.height as f32) });
+                    let amount = strength * strength;
+                    [ mix(scene_color[0], color.x, amount), mix(scene_color[1], color.y, amount), mix(scene_color[2], color.z, amount) ]
+                }),
+                PostProcessEffects::DESATURATE => self.filter_screen(target, |uv, scene| {
+                    let scene_color = scene(uv);
+                    let luminance = 0.299 * scene_color[0] + 0.587 * scene_color[1] + 0.114 * scene_color[2];
+                    [ mix(scene_color[0], luminance * color.x, strength), mix(scene_color[1], luminance * color.y, strength), mix(scene_color[2], luminance * color.z, strength) ]
+                }),
+            }
+        }
+    }
+
+    //Replaces every pixel with an opaque color computed from the scene so far, like the post process shaders that read the render target.
+    //The uv starts at 0 in the bottom left corner, the scene is sampled bilinear.
+    fn filter_screen<F>(&self, target: &mut [Color], color_at: F)
+        where F: Fn(cgmath::Vector2<f32>, &dyn Fn(cgmath::Vector2<f32>) -> Color) -> [f32;3]
+    {
+        let scene_pixels = target.to_vec();
+        let scene = |uv: cgmath::Vector2<f32>| bilinear(self.width, self.height, uv.x, 1.0 - uv.y, |x, y, channel| scene_pixels[(y * self.width + x) as usize][channel]);
+
+        for y in 0..self.height
+        {
+            for x in 0..self.width
+            {
+                let uv = cgmath::Vector2 { x: (x as f32 + 0.5) / self.width as f32, y: 1.0 - (y as f32 + 0.5) / self.height as f32 };
+                let color = color_at(uv, &scene);
+                target[(y * self.width + x) as usize] = [ color[0].clamp(0.0, 1.0), color[1].clamp(0.0, 1.0), color[2].clamp(0.0, 1.0), 1.0 ];
+            }
+        }
+    }
+
+    //Center of the pixel, the top left pixel is at -1, 1
+    fn to_clip_space(&self, x: u32, y: u32) -> cgmath::Vector2<f32>
+    {
+        cgmath::Vector2 { x: (x as f32 + 0.5) / self.width as f32 * 2.0 - 1.0, y: 1.0 - (y as f32 + 0.5) / self.height as f32 * 2.0 }
+    }
+
+    //Blends the color of every pixel whose center is inside the quad, which is turned counterclockwise around its center by the rotation.
+    //The uv starts at 0 in the bottom left corner, as in the vertex shaders.
+    fn fill_quad<F>(&self, target: &mut [Color], center: cgmath::Vector2<f32>, half_size: cgmath::Vector2<f32>, rotation: f32, color_at: F)
+        where F: Fn(cgmath::Vector2<f32>) -> Color
+    {
+        if half_size.x <= 0.0 || half_size.y <= 0.0
+        {
+            return;
+        }
+
+        //Bounding box of the turned quad
+        let extent = cgmath::Vector2 {
+            x: (half_size.x * rotation.cos()).abs() + (half_size.y * rotation.sin()).abs(),
+            y: (half_size.x * rotation.sin()).abs() + (half_size.y * rotation.cos()).abs(),
+        };
+
+        let to_pixel_x = |clip: f32| (clip + 1.0) / 2.0 * self.width as f32;
+        let to_pixel_y = |clip: f32| (1.0 - clip) / 2.0 * self.height as f32;
+
+        let min_x = to_pixel_x(center.x - extent.x).max(0.0).floor() as u32;
+        let max_x = (to_pixel_x(center.x + extent.x).ceil().max(0.0) as u32).min(self.width);
+        let min_y = to_pixel_y(center.y + extent.y).max(0.0).floor() as u32;
+        let max_y = (to_pixel_y(center.y - extent.y).ceil().max(0.0) as u32).min(self.height);
+
+        for y in min_y..max_y
+        {
+            for x in min_x..max_x
+            {
+                let local = rotate(self.to_clip_space(x, y) - center, -rotation);
+                let uv = cgmath::Vector2 {
+                    x: (local.x + half_size.x) / (2.0 * half_size.x),
+                    y: (local.y + half_size.y) / (2.0 * half_size.y),
+                };
+
+                if uv.x < 0.0 || uv.x >= 1.0 || uv.y < 0.0 || uv.y >= 1.0
+                {
+                    continue;
+                }
+
+                blend(&mut target[(y * self.width + x) as usize], color_at(uv));
+            }
+        }
+    }
+}
+
+//Whether no wall is between the pixel and the light, both in tiles. The tiles of both do not block, as in the lighting shader.
+fn is_visible(level: &LevelViewModel, occluding_tiles: &[i32], pixel: cgmath::Vector2<f32>, light: cgmath::Vector2<f32>) -> bool
+{
+    const SHADOW_STEPS: i32 = 32;
+
+    let floor = |v: cgmath::Vector2<f32>| cgmath::Vector2 { x: v.x.floor(), y: v.y.floor() };
+    let pixel_tile = floor(pixel);
+    let light_tile = floor(light);
+    for i in 1..SHADOW_STEPS
+    {
+        let tile = floor(pixel + (light - pixel) * (i as f32 / SHADOW_STEPS as f32));
+        if tile == pixel_tile || tile == light_tile || tile.x < 0.0 || tile.y < 0.0 || tile.x >= level.width || tile.y >= level.height
+        {
+            continue;
+        }
+
+        let map_tile = (tile.y * level.width + tile.x) as usize;
+        if level.data.get(map_tile).is_some_and(|x| occluding_tiles.contains(x))
+        {
+            return false;
+        }
+    }
+    true
+}
+
+//Same transform as in the shaders
+fn to_screen(position: cgmath::Vector2<f32>, camera: &CameraViewModel) -> cgmath::Vector2<f32>
+{
+    camera.to_screen(position)
+}
+
+//Counterclockwise by the angle in radians
+fn rotate(v: cgmath::Vector2<f32>, angle: f32) -> cgmath::Vector2<f32>
+{
+    cgmath::Vector2 { x: angle.cos() * v.x - angle.sin() * v.y, y: angle.sin() * v.x + angle.cos() * v.y }
+}
+
+//Bilinear lookup with the texture coordinates of WebGL, clamped to the edge
+fn sample(texture: &image::RgbaImage, u: f32, v: f32) -> Color
+{
+    bilinear(texture.width(), texture.height(), u, v, |x, y, channel| texture.get_pixel(x, y)[channel] as f32 / 255.0)
+}
+
+//The getter returns the channel of the pixel, v starts at 0 in the first row
+fn bilinear<G>(width: u32, height: u32, u: f32, v: f32, get: G) -> Color
+    where G: Fn(u32, u32, usize) -> f32
+{
+    let x = (u * width as f32 - 0.5).clamp(0.0, width as f32 - 1.0);
+    let y = (v * height as f32 - 0.5).clamp(0.0, height as f32 - 1.0);
+
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+    let fraction_x = x - x0 as f32;
+    let fraction_y = y - y0 as f32;
+
+    let mut color = [0.0;4];
+    for (channel, value) in color.iter_mut().enumerate()
+    {
+        let top = mix(get(x0, y0, channel), get(x1, y0, channel), fraction_x);
+        let bottom = mix(get(x0, y1, channel), get(x1, y1, channel), fraction_x);
+        *value = mix(top, bottom, fraction_y);
+    }
+    color
+}
+
+//SRC_ALPHA, ONE_MINUS_SRC_ALPHA for all channels, as set by enable_alpha_blending
+fn blend(destination: &mut Color, source: Color)
+{
+    let alpha = source[3].clamp(0.0, 1.0);
+    for (destination, source) in destination.iter_mut().zip(source.iter())
+    {
+        *destination = source.clamp(0.0, 1.0) * alpha + *destination * (1.0 - alpha);
+    }
+}
+
+fn glsl_mod(x: f32, y: f32) -> f32
+{
+    x - y * (x / y).floor()
+}
+
+fn mix(x: f32, y: f32, a: f32) -> f32
+{
+    x * (1.0 - a) + y * a
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32
+{
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn to_byte(value: f32) -> u8
+{
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
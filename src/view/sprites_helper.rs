@@ -1,76 +1,50 @@
-use crate::view::shader_utils;
+use crate::view::render_backend::{RenderBackend, ProgramId, VertexArrayId, BufferId};
 
 use cgmath;
-use web_sys::{WebGl2RenderingContext, WebGlProgram};
 
-pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgram, String> 
+//Per instance attributes as (location, number of floats): center, size, uv rect and alpha
+pub const INSTANCE_ATTRIBUTES: [(u32, i32);4] = [ (2, 2), (3, 2), (4, 4), (5, 1) ];
+
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String> 
 {
-    let vert_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::VERTEX_SHADER,
-        r#"#version 300 es
+    let vert_shader = r#"#version 300 es
+
+        in vec4 position;
+        in vec2 uvIn;
+
+        in vec2 center;
+        in vec2 size;
+        in vec4 uvRectIn;
+        in float alphaIn;
 
-        uniform vec2[10] sizes;
-        uniform vec2[10] positions;
-        uniform int[10] tileMapIndices;
+        uniform vec2 cameraPosition;
+        uniform float cameraZoom;
+        uniform float cameraRotation;
 
         out vec2 uv;
-        flat out int tileMapIndex;
+        flat out vec4 uvRect;
+        flat out float alpha;
 
         void main()
         {
-            int idx = gl_VertexID / 6;
-            vec2 centerPos = positions[idx];
-            tileMapIndex = tileMapIndices[idx];
-            
-            int subIdx = gl_VertexID % 6;
-            
-            if(subIdx == 0)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(1, -1), 0, 1);
-                uv = vec2(1.0, 0.0);
-            }
-            else if(subIdx == 1)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(1, 1), 0, 1);
-                uv = vec2(1.0, 1.0);
-            }
-            else if(subIdx == 2)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(-1, 1), 0, 1);
-                uv = vec2(0.0, 1.0);
-            }
-            else if(subIdx == 3)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(1, -1), 0, 1);
-                uv = vec2(1.0, 0.0);
-            }
-            else if(subIdx == 4)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(-1, 1), 0, 1);
-                uv = vec2(0.0, 1.0);
-            }
-            else// if(subIdx == 5)
-            {
-                gl_Position = vec4(centerPos + (sizes[idx] / 2.0) * vec2(-1, -1), 0, 1);
-                uv = vec2(0.0, 0.0);
-            }
+            //The quad has a size of 1 around the origin
+            mat2 rotate = mat2(cos(cameraRotation), sin(cameraRotation), -sin(cameraRotation), cos(cameraRotation));
+            gl_Position = vec4(rotate * ((center + position.xy * size - cameraPosition) * cameraZoom), 0, 1);
+            uv = uvIn;
+            uvRect = uvRectIn;
+            alpha = alphaIn;
         }
-    "#,
-    )?;
+    "#;
 
-    let frag_shader = shader_utils::compile_shader(
-        &context,
-        WebGl2RenderingContext::FRAGMENT_SHADER,
-        r#"#version 300 es
+    let frag_shader = r#"#version 300 es
         precision highp float;
 
-        uniform float tileMapWidth;
-        uniform float tileMapHeight;
         uniform sampler2D tileMap;
         
         in vec2 uv;
-        flat in int tileMapIndex;
+        //Min corner in xy, max corner in zw, measured from the top left of the sprite tile map
+        flat in vec4 uvRect;
+        flat in float alpha;
 
         out vec4 outColor;
 
@@ -79,54 +53,27 @@ pub fn initialize_shader(context: &WebGl2RenderingContext) -> Result<WebGlProgra
             //Flip, because bmp is not flipped in the file
             vec2 uv = vec2(uv.x, 1.0 - uv.y);
 
-            float tileToUseCol = mod(float(tileMapIndex), tileMapWidth);
-            float tileToUseRow = floor(float(tileMapIndex) / tileMapHeight);
-
-            float startTileX = tileToUseCol / tileMapWidth;
-            float tileSizeX = 1.0 / tileMapWidth;
-            float tiledUvX = mix(startTileX, startTileX + tileSizeX, uv.x);
-
-            float startTileY = tileToUseRow / tileMapHeight;
-            float tileSizeY = 1.0 / tileMapHeight;
-            float tiledUvY = mix(startTileY, startTileY + tileSizeY, uv.y);
-
-            outColor = texture(tileMap, vec2(tiledUvX, tiledUvY));
-
-            // outColor = vec4(1.0, 0.0, 0.0, 1.0);
+            outColor = texture(tileMap, mix(uvRect.xy, uvRect.zw, uv));
+            outColor.a *= alpha;
         }
-    "#,
-    )?;
-
-    return shader_utils::link_program(
-        &context,
-        &vert_shader,
-        &frag_shader,
-        vec![(0, "position"), (1, "uv")],
-    );
-}
+    "#;
 
-pub fn update_sizes(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_sizes: [cgmath::Vector2<f32>;10]) -> Result<(), String>
-{
-    shader_utils::set_uniform2f_arr10(context, shader, new_sizes, "sizes")?;
-    Ok(())
+    backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uvIn"), (2, "center"), (3, "size"), (4, "uvRectIn"), (5, "alphaIn")])
 }
 
-pub fn update_positions(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_positions: [cgmath::Vector2<f32>;10]) -> Result<(), String>
+pub fn initialize_quad(backend: &mut dyn RenderBackend) -> Result<(VertexArrayId, BufferId), String>
 {
-    shader_utils::set_uniform2f_arr10(context, shader, new_positions, "positions")?;
-    Ok(())
+    let (_, vao, instance_buffer) = backend.create_instanced_quad(cgmath::Vector2 { x: 0.0, y: 0.0 }, cgmath::Vector2 { x: 1.0, y: 1.0 }, &INSTANCE_ATTRIBUTES)?;
+    Ok((vao, instance_buffer))
 }
 
-pub fn update_tile_map_indices(context: &WebGl2RenderingContext, shader: &WebGlProgram, new_indices: [i32;10]) -> Result<(), String>
-{       
-    shader_utils::set_uniform1i_arr10(context, shader, new_indices, "tileMapIndices")?;
-    Ok(())
+//Uploads the sprites in the layout of INSTANCE_ATTRIBUTES
+pub fn update_instances(backend: &mut dyn RenderBackend, instance_buffer: BufferId, positions: &[cgmath::Vector2<f32>], sizes: &[cgmath::Vector2<f32>], uv_rects: &[cgmath::Vector4<f32>], alphas: &[f32])
+{
+    let mut data: std::vec::Vec<f32> = std::vec::Vec::with_capacity(positions.len() * 9);
+    for i in 0..positions.len()
+    {
+        data.extend_from_slice(&[ positions[i].x, positions[i].y, sizes[i].x, sizes[i].y, uv_rects[i].x, uv_rects[i].y, uv_rects[i].z, uv_rects[i].w, alphas[i] ]);
+    }
+    backend.update_instance_buffer(instance_buffer, &data);
 }
-
-pub fn set_tile_map_uniforms(context: &WebGl2RenderingContext, program: &WebGlProgram, width: f32, height: f32) -> Result<(), String>
-{       
-    context.use_program(Some(&program));
-    shader_utils::set_uniform1f(context, program, width, "tileMapWidth")?;
-    shader_utils::set_uniform1f(context, program, height, "tileMapHeight")?;
-    Ok(())
-}
\ No newline at end of file
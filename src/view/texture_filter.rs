@@ -0,0 +1,25 @@
+//How textures are sampled between their pixels. Nearest keeps pixel art crisp, linear smooths it.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TextureFilter
+{
+    Nearest = 0,
+    Linear = 1,
+}
+
+impl TextureFilter
+{
+    pub fn from(filter_code: u8) -> Result<TextureFilter, String>
+    {
+        match filter_code
+        {
+            0 => Ok(TextureFilter::Nearest),
+            1 => Ok(TextureFilter::Linear),
+            _ => Err(format!("Texture filter {} not found", filter_code)),
+        }
+    }
+
+    pub fn is_linear(&self) -> bool
+    {
+        *self == TextureFilter::Linear
+    }
+}
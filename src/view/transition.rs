@@ -0,0 +1,57 @@
+use cgmath;
+
+//How one scene is replaced by the next, e.g. when a level is loaded or restarted
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TransitionKind
+{
+    //The old scene fades to black, then the new one fades in
+    Fade = 0,
+    //A circle closes on the fox in the old scene, then opens on the fox in the new one
+    Iris = 1,
+    //The new scene appears through a hole that opens on the fox, on top of the old one
+    Hole = 2,
+}
+
+impl TransitionKind
+{
+    pub fn from(kind_code: u8) -> Result<TransitionKind, String>
+    {
+        match kind_code
+        {
+            0 => Ok(TransitionKind::Fade),
+            1 => Ok(TransitionKind::Iris),
+            2 => Ok(TransitionKind::Hole),
+            _ => Err(format!("Transition {} not found", kind_code)),
+        }
+    }
+}
+
+//The centers are in uvs of the screen, from 0 in the bottom left to 1 in the top right
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transition
+{
+    pub kind: TransitionKind,
+    pub running_time: f32,
+    pub duration: f32,
+    pub old_center: cgmath::Vector2<f32>,
+    pub new_center: cgmath::Vector2<f32>,
+}
+
+impl Transition
+{
+    pub fn new(kind: TransitionKind, duration: f32, old_center: cgmath::Vector2<f32>, new_center: cgmath::Vector2<f32>) -> Transition
+    {
+        Transition { kind, running_time: 0.0, duration, old_center, new_center }
+    }
+
+    //From 0 when the old scene is shown to 1 when only the new one is left
+    pub fn get_progress(&self) -> f32
+    {
+        if self.duration > 0.0 { (self.running_time / self.duration).clamp(0.0, 1.0) } else { 1.0 }
+    }
+
+    pub fn is_finished(&self) -> bool
+    {
+        self.running_time >= self.duration
+    }
+}
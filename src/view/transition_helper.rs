@@ -0,0 +1,88 @@
+use crate::view::render_backend::{RenderBackend, ProgramId, UniformValue};
+
+//The new scene is on unit 0, the snapshot of the old one on this one
+pub const SNAPSHOT_TEXTURE_UNIT: u32 = 1;
+
+//Scales the scene up to the canvas like the upscale shader, mixed with the snapshot of the old scene
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let vert_shader = r#"#version 300 es
+
+        in vec4 position;
+        in vec2 uvIn;
+
+        out vec2 uv;
+
+        void main()
+        {
+            gl_Position = position;
+            uv = uvIn;
+        }
+    "#;
+
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D scene;
+        uniform sampler2D snapshot;
+        //0 = fade, 1 = iris, 2 = hole, see transition.rs
+        uniform int kind;
+        uniform float progress;
+        uniform vec2 oldCenter;
+        uniform vec2 newCenter;
+        //Width by height of the viewport, so circles stay round
+        uniform float aspect;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        float getDistance(vec2 center)
+        {
+            vec2 offset = uv - center;
+            offset.x *= aspect;
+            return length(offset);
+        }
+
+        void main()
+        {
+            vec3 oldColor = texture(snapshot, uv).rgb;
+            vec3 newColor = texture(scene, uv).rgb;
+            //Big enough to cover the whole screen from any center, even with the ragged edge of the hole
+            float maxRadius = 2.0 * max(aspect, 1.0);
+            vec3 color;
+
+            if(kind == 0)
+            {
+                color = progress < 0.5 ? oldColor * (1.0 - progress * 2.0) : newColor * (progress * 2.0 - 1.0);
+            }
+            else if(kind == 1)
+            {
+                if(progress < 0.5)
+                {
+                    color = getDistance(oldCenter) < maxRadius * (1.0 - progress * 2.0) ? oldColor : vec3(0.0);
+                }
+                else
+                {
+                    color = getDistance(newCenter) < maxRadius * (progress * 2.0 - 1.0) ? newColor : vec3(0.0);
+                }
+            }
+            else
+            {
+                vec2 offset = uv - newCenter;
+                offset.x *= aspect;
+                float angle = atan(offset.y, offset.x);
+                //Ragged like freshly dug earth
+                float edge = 1.0 + 0.15 * sin(angle * 7.0) + 0.08 * sin(angle * 13.0 + 1.7);
+                color = length(offset) < maxRadius * progress * edge ? newColor : oldColor;
+            }
+
+            outColor = vec4(color, 1.0);
+        }
+    "#;
+
+    let program = backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uvIn")])?;
+    backend.set_uniform(program, "scene", UniformValue::Int(0))?;
+    backend.set_uniform(program, "snapshot", UniformValue::Int(SNAPSHOT_TEXTURE_UNIT as i32))?;
+    Ok(program)
+}
@@ -0,0 +1,37 @@
+use crate::view::render_backend::{RenderBackend, ProgramId};
+
+//Copies the scene from its render target to the viewport of the canvas, drawn with the screen filling quad of the background
+pub fn initialize_shader(backend: &mut dyn RenderBackend) -> Result<ProgramId, String>
+{
+    let vert_shader = r#"#version 300 es
+
+        in vec4 position;
+        in vec2 uvIn;
+
+        out vec2 uv;
+
+        void main()
+        {
+            gl_Position = position;
+            uv = uvIn;
+        }
+    "#;
+
+    let frag_shader = r#"#version 300 es
+        precision highp float;
+
+        uniform sampler2D scene;
+
+        in vec2 uv;
+
+        out vec4 outColor;
+
+        void main()
+        {
+            //Opaque, so the alpha that blending left in the render target does not let the canvas shine through
+            outColor = vec4(texture(scene, uv).rgb, 1.0);
+        }
+    "#;
+
+    backend.create_program(vert_shader, frag_shader, &[(0, "position"), (1, "uvIn")])
+}
@@ -0,0 +1,224 @@
+use crate::view::shader_utils;
+use crate::view::render_backend::{RenderBackend, ProgramId, TextureId, VertexArrayId, BufferId, FramebufferId, UniformValue};
+
+use cgmath;
+use web_sys::{WebGlProgram, WebGl2RenderingContext, WebGlTexture, WebGlVertexArrayObject, WebGlBuffer, WebGlFramebuffer};
+
+pub struct WebGlBackend
+{
+    context: WebGl2RenderingContext,
+    programs: std::collections::HashMap<ProgramId, WebGlProgram>,
+    textures: std::collections::HashMap<TextureId, WebGlTexture>,
+    vertex_arrays: std::collections::HashMap<VertexArrayId, WebGlVertexArrayObject>,
+    buffers: std::collections::HashMap<BufferId, WebGlBuffer>,
+    framebuffers: std::collections::HashMap<FramebufferId, WebGlFramebuffer>,
+    next_id: u32,
+}
+
+impl WebGlBackend
+{
+    pub fn new(context: WebGl2RenderingContext) -> WebGlBackend
+    {
+        WebGlBackend {
+            context,
+            programs: std::collections::HashMap::new(),
+            textures: std::collections::HashMap::new(),
+            vertex_arrays: std::collections::HashMap::new(),
+            buffers: std::collections::HashMap::new(),
+            framebuffers: std::collections::HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn create_id(&mut self) -> u32
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn get_program(&self, program: ProgramId) -> Result<&WebGlProgram, String>
+    {
+        self.programs.get(&program).ok_or(format!("Program {} not found", program))
+    }
+}
+
+impl RenderBackend for WebGlBackend
+{
+    fn create_program(&mut self, vertex_source: &str, fragment_source: &str, attributes: &[(u32, &str)]) -> Result<ProgramId, String>
+    {
+        let vert_shader = shader_utils::compile_shader(&self.context, WebGl2RenderingContext::VERTEX_SHADER, vertex_source)?;
+        let frag_shader = shader_utils::compile_shader(&self.context, WebGl2RenderingContext::FRAGMENT_SHADER, fragment_source)?;
+        let program = shader_utils::link_program(&self.context, &vert_shader, &frag_shader, attributes.to_vec())?;
+
+        let id = self.create_id();
+        self.programs.insert(id, program);
+        Ok(id)
+    }
+
+    fn create_texture(&mut self, image: image::RgbaImage, linear: bool) -> Result<TextureId, String>
+    {
+        let texture = shader_utils::initialize_texture(&self.context, image, linear)?;
+
+        let id = self.create_id();
+        self.textures.insert(id, texture);
+        Ok(id)
+    }
+
+    fn create_render_target(&mut self, width: i32, height: i32, linear: bool) -> Result<(FramebufferId, TextureId), String>
+    {
+        let (framebuffer, texture) = shader_utils::initialize_render_target(&self.context, width, height, linear)?;
+
+        let framebuffer_id = self.create_id();
+        self.framebuffers.insert(framebuffer_id, framebuffer);
+        let texture_id = self.create_id();
+        self.textures.insert(texture_id, texture);
+        Ok((framebuffer_id, texture_id))
+    }
+
+    fn delete_render_target(&mut self, framebuffer: FramebufferId, texture: TextureId)
+    {
+        if let Some(framebuffer) = self.framebuffers.remove(&framebuffer)
+        {
+            self.context.delete_framebuffer(Some(&framebuffer));
+        }
+        self.delete_texture(texture);
+    }
+
+    fn create_data_texture(&mut self, width: u32, height: u32, data: &[i32]) -> Result<TextureId, String>
+    {
+        let texture = shader_utils::initialize_data_texture(&self.context, width as i32, height as i32, data)?;
+
+        let id = self.create_id();
+        self.textures.insert(id, texture);
+        Ok(id)
+    }
+
+    fn update_data_texture(&mut self, texture: TextureId, x: u32, y: u32, width: u32, height: u32, data: &[i32]) -> Result<(), String>
+    {
+        let texture = self.textures.get(&texture).ok_or(format!("Texture {} not found", texture))?;
+        shader_utils::update_data_texture(&self.context, texture, x as i32, y as i32, width as i32, height as i32, data)
+    }
+
+    fn delete_texture(&mut self, texture: TextureId)
+    {
+        if let Some(texture) = self.textures.remove(&texture)
+        {
+            self.context.delete_texture(Some(&texture));
+        }
+    }
+
+    fn set_texture_filter(&mut self, texture: TextureId, linear: bool)
+    {
+        if let Some(texture) = self.textures.get(&texture)
+        {
+            shader_utils::set_texture_filter(&self.context, texture, linear);
+        }
+    }
+
+    fn create_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>) -> Result<(i32, VertexArrayId), String>
+    {
+        let (vertex_count, vertex_array) = shader_utils::initialize_quad_with_uvs(&self.context, center, size)?;
+
+        let id = self.create_id();
+        self.vertex_arrays.insert(id, vertex_array);
+        Ok((vertex_count as i32, id))
+    }
+
+    fn create_instanced_quad(&mut self, center: cgmath::Vector2<f32>, size: cgmath::Vector2<f32>, instance_attributes: &[(u32, i32)]) -> Result<(i32, VertexArrayId, BufferId), String>
+    {
+        let (vertex_count, vertex_array) = shader_utils::initialize_quad_with_uvs(&self.context, center, size)?;
+        let buffer = shader_utils::initialize_instance_buffer(&self.context, &vertex_array, instance_attributes)?;
+
+        let vertex_array_id = self.create_id();
+        self.vertex_arrays.insert(vertex_array_id, vertex_array);
+        let buffer_id = self.create_id();
+        self.buffers.insert(buffer_id, buffer);
+        Ok((vertex_count as i32, vertex_array_id, buffer_id))
+    }
+
+    fn update_instance_buffer(&mut self, buffer: BufferId, data: &[f32])
+    {
+        if let Some(buffer) = self.buffers.get(&buffer)
+        {
+            shader_utils::update_buffer(&self.context, buffer, data);
+        }
+    }
+
+    fn set_uniform(&mut self, program: ProgramId, name: &str, value: UniformValue) -> Result<(), String>
+    {
+        let program = self.get_program(program)?;
+        match value
+        {
+            UniformValue::Float(data) => shader_utils::set_uniform1f(&self.context, program, data, name),
+            UniformValue::Int(data) => shader_utils::set_uniform1i(&self.context, program, data, name),
+            UniformValue::Vec2(data) => shader_utils::set_uniform2f(&self.context, program, data, name),
+            UniformValue::Vec3(data) => shader_utils::set_uniform3f(&self.context, program, data, name),
+            UniformValue::FloatArray(data) => shader_utils::set_uniform1f_arr(&self.context, program, &data, name),
+            UniformValue::Vec2Array(data) => shader_utils::set_uniform2f_arr(&self.context, program, &data, name),
+            UniformValue::Vec4Array(data) => shader_utils::set_uniform4f_arr(&self.context, program, &data, name),
+            UniformValue::IntArray(data) => shader_utils::set_uniform1i_arr(&self.context, program, &data, name),
+        }
+    }
+
+    fn use_program(&mut self, program: ProgramId)
+    {
+        self.context.use_program(self.programs.get(&program));
+    }
+
+    fn bind_vertex_array(&mut self, vertex_array: VertexArrayId)
+    {
+        self.context.bind_vertex_array(self.vertex_arrays.get(&vertex_array));
+    }
+
+    fn bind_texture(&mut self, texture: TextureId)
+    {
+        self.context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, self.textures.get(&texture));
+    }
+
+    fn bind_texture_to_unit(&mut self, texture: TextureId, unit: u32)
+    {
+        self.context.active_texture(WebGl2RenderingContext::TEXTURE0 + unit);
+        self.context.bind_texture(WebGl2RenderingContext::TEXTURE_2D, self.textures.get(&texture));
+        self.context.active_texture(WebGl2RenderingContext::TEXTURE0);
+    }
+
+    fn bind_render_target(&mut self, framebuffer: Option<FramebufferId>)
+    {
+        let framebuffer = framebuffer.and_then(|x| self.framebuffers.get(&x));
+        self.context.bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, framebuffer);
+    }
+
+    fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32)
+    {
+        self.context.viewport(x, y, width, height);
+    }
+
+    fn enable_alpha_blending(&mut self)
+    {
+        self.context.enable(WebGl2RenderingContext::BLEND);
+        self.context.blend_func(WebGl2RenderingContext::SRC_ALPHA, WebGl2RenderingContext::ONE_MINUS_SRC_ALPHA);
+    }
+
+    fn enable_multiply_blending(&mut self)
+    {
+        self.context.enable(WebGl2RenderingContext::BLEND);
+        self.context.blend_func(WebGl2RenderingContext::DST_COLOR, WebGl2RenderingContext::ZERO);
+    }
+
+    fn clear(&mut self, color: [f32;4])
+    {
+        self.context.clear_color(color[0], color[1], color[2], color[3]);
+        self.context.clear(WebGl2RenderingContext::COLOR_BUFFER_BIT);
+    }
+
+    fn draw_triangles(&mut self, first_vertex: i32, vertex_count: i32)
+    {
+        self.context.draw_arrays(WebGl2RenderingContext::TRIANGLES, first_vertex, vertex_count);
+    }
+
+    fn draw_triangles_instanced(&mut self, first_vertex: i32, vertex_count: i32, instance_count: i32)
+    {
+        self.context.draw_arrays_instanced(WebGl2RenderingContext::TRIANGLES, first_vertex, vertex_count, instance_count);
+    }
+}
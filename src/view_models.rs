@@ -2,10 +2,19 @@ use cgmath;
 
 pub struct SpritesViewModel
 {
-    pub sizes: [cgmath::Vector2<f32>;10], 
-    pub positions: [cgmath::Vector2<f32>;10], 
-    pub tile_map_indices: [i32;10], 
-    pub count: i32,
+    pub sizes: std::vec::Vec<cgmath::Vector2<f32>>, 
+    pub positions: std::vec::Vec<cgmath::Vector2<f32>>, 
+    //Frame indices in the sprite atlas
+    pub frames: std::vec::Vec<usize>, 
+    pub alphas: std::vec::Vec<f32>,
+}
+
+impl SpritesViewModel
+{
+    pub fn get_count(&self) -> usize
+    {
+        self.positions.len()
+    }
 }
 
 pub struct LevelViewModel
@@ -17,11 +26,58 @@ pub struct LevelViewModel
 
 pub struct ParticlesViewModel
 {
-    pub positions: [cgmath::Vector2<f32>;10],
-    pub max_speeds: [f32;10],
-    pub running_times: [f32;10],
-    pub max_running_times: [f32;10],
-    pub count: i32
+    pub positions: std::vec::Vec<cgmath::Vector2<f32>>,
+    pub max_speeds: std::vec::Vec<f32>,
+    pub running_times: std::vec::Vec<f32>,
+    pub max_running_times: std::vec::Vec<f32>,
+}
+
+impl ParticlesViewModel
+{
+    pub fn get_count(&self) -> usize
+    {
+        self.positions.len()
+    }
+}
+
+//Darkness of a night level and the lights that cut into it. Positions are in the level like the sprites, radii are in tiles.
+pub struct LightsViewModel
+{
+    //Light that reaches everywhere, black is complete darkness
+    pub ambient: cgmath::Vector3<f32>,
+    pub positions: std::vec::Vec<cgmath::Vector2<f32>>,
+    pub radii: std::vec::Vec<f32>,
+    pub colors: std::vec::Vec<cgmath::Vector3<f32>>,
+    pub intensities: std::vec::Vec<f32>,
+    //Tiles of the map that block light and cast shadows
+    pub occluding_tiles: std::vec::Vec<i32>,
+}
+
+impl LightsViewModel
+{
+    pub fn get_count(&self) -> usize
+    {
+        self.positions.len()
+    }
+}
+
+//The world position in the center of the screen, how much the world is scaled, 1 shows the whole level,
+//and how much the screen is turned counterclockwise in radians
+pub struct CameraViewModel
+{
+    pub position: cgmath::Vector2<f32>,
+    pub zoom: f32,
+    pub rotation: f32,
+}
+
+impl CameraViewModel
+{
+    //Where a position in the level ends up on screen, in clip space like the vertex shaders
+    pub fn to_screen(&self, position: cgmath::Vector2<f32>) -> cgmath::Vector2<f32>
+    {
+        let v = (position - self.position) * self.zoom;
+        cgmath::Vector2 { x: self.rotation.cos() * v.x - self.rotation.sin() * v.y, y: self.rotation.sin() * v.x + self.rotation.cos() * v.y }
+    }
 }
 
 pub struct PostProcessEffect
@@ -29,6 +85,139 @@ pub struct PostProcessEffect
     pub name: PostProcessEffects,
     pub running_time: f32,
     pub max_running_time: f32,
+    pub parameters: PostProcessParameters,
+}
+
+impl PostProcessEffect
+{
+    //From 0 when the effect starts to 1 when it ends
+    pub fn get_progress(&self) -> f32
+    {
+        if self.max_running_time > 0.0 { (self.running_time / self.max_running_time).clamp(0.0, 1.0) } else { 1.0 }
+    }
+}
+
+//How a parameter moves from its start to its end value over the lifetime of an effect
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Easing
+{
+    Linear = 0,
+    //Starts slow and speeds up
+    EaseIn = 1,
+    //Starts fast and slows down
+    EaseOut = 2,
+    EaseInOut = 3,
+}
+
+impl Easing
+{
+    pub fn from(easing_code: u8) -> Result<Easing, String>
+    {
+        match easing_code
+        {
+            0 => Ok(Easing::Linear),
+            1 => Ok(Easing::EaseIn),
+            2 => Ok(Easing::EaseOut),
+            3 => Ok(Easing::EaseInOut),
+            _ => Err(format!("Easing {} not found", easing_code)),
+        }
+    }
+
+    //Progress and result go from 0 to 1
+    pub fn apply(&self, progress: f32) -> f32
+    {
+        let t = progress.clamp(0.0, 1.0);
+        match self
+        {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+//A parameter that goes from start to end over the lifetime of an effect
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Eased<T>
+{
+    pub start: T,
+    pub end: T,
+    pub easing: Easing,
+}
+
+impl<T> Eased<T>
+    where T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<f32, Output = T>
+{
+    pub fn new(start: T, end: T, easing: Easing) -> Eased<T>
+    {
+        Eased { start, end, easing }
+    }
+
+    //Same value over the whole lifetime
+    pub fn constant(value: T) -> Eased<T>
+    {
+        Eased { start: value, end: value, easing: Easing::Linear }
+    }
+
+    pub fn get(&self, progress: f32) -> T
+    {
+        self.start + (self.end - self.start) * self.easing.apply(progress)
+    }
+}
+
+//What an effect looks like, not every effect uses every parameter
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PostProcessParameters
+{
+    pub color: Eased<cgmath::Vector3<f32>>,
+    //A size in parts of the screen, e.g. the distance from the center where the vignette starts, 0.5 is the middle of the edges
+    pub radius: Eased<f32>,
+    //0 leaves the screen as it is, 1 is the full effect
+    pub strength: Eased<f32>,
+}
+
+impl PostProcessParameters
+{
+    //How each effect looks when it is started for an event, e.g. a red flash that fades out when the fox gets bitten
+    pub fn default_for(effect_type: PostProcessEffects) -> PostProcessParameters
+    {
+        match effect_type
+        {
+            PostProcessEffects::VIGNETTE => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+                radius: Eased::constant(0.5),
+                strength: Eased::constant(1.0),
+            },
+            PostProcessEffects::FLASH => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 1.0, y: 0.0, z: 0.0 }),
+                radius: Eased::constant(0.0),
+                strength: Eased::new(0.5, 0.0, Easing::Linear),
+            },
+            PostProcessEffects::CRT => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+                radius: Eased::constant(0.1),
+                strength: Eased::constant(0.3),
+            },
+            PostProcessEffects::CHROMATIC_ABERRATION => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+                radius: Eased::constant(0.0),
+                strength: Eased::new(0.05, 0.0, Easing::EaseIn),
+            },
+            //Dissolves into black
+            PostProcessEffects::PIXELATE => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+                radius: Eased::constant(0.1),
+                strength: Eased::new(0.0, 1.0, Easing::EaseIn),
+            },
+            //Grey at once, the colors come back slowly
+            PostProcessEffects::DESATURATE => PostProcessParameters {
+                color: Eased::constant(cgmath::Vector3 { x: 1.0, y: 1.0, z: 1.0 }),
+                radius: Eased::constant(0.0),
+                strength: Eased::new(1.0, 0.0, Easing::EaseIn),
+            },
+        }
+    }
 }
 
 pub struct PostProcessViewModel
@@ -36,17 +225,34 @@ pub struct PostProcessViewModel
     pub effects: std::vec::Vec<PostProcessEffect>,
 }
 
-#[derive(Copy, Clone, Hash)]
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
 pub enum PostProcessEffects
 {
     VIGNETTE = 0,
+    FLASH = 1,
+    CRT = 2,
+    CHROMATIC_ABERRATION = 3,
+    PIXELATE = 4,
+    DESATURATE = 5,
 }
 
-impl PartialEq for PostProcessEffects
+impl PostProcessEffects
 {
-    fn eq(&self, other: &Self) -> bool {
-        self == other
-    }
-}
+    pub const ALL: [PostProcessEffects; 6] = [ PostProcessEffects::VIGNETTE, PostProcessEffects::FLASH, PostProcessEffects::CRT,
+        PostProcessEffects::CHROMATIC_ABERRATION, PostProcessEffects::PIXELATE, PostProcessEffects::DESATURATE ];
 
-impl std::cmp::Eq for PostProcessEffects { }
\ No newline at end of file
+    pub fn from(effect_code: u8) -> Result<PostProcessEffects, String>
+    {
+        match effect_code
+        {
+            0 => Ok(PostProcessEffects::VIGNETTE),
+            1 => Ok(PostProcessEffects::FLASH),
+            2 => Ok(PostProcessEffects::CRT),
+            3 => Ok(PostProcessEffects::CHROMATIC_ABERRATION),
+            4 => Ok(PostProcessEffects::PIXELATE),
+            5 => Ok(PostProcessEffects::DESATURATE),
+            _ => Err(format!("Post process effect {} not found", effect_code)),
+        }
+    }
+}
\ No newline at end of file
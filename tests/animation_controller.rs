@@ -0,0 +1,192 @@
+//! Native tests of the animation controller: play modes, transitions between animations and frame events.
+
+use for_fox_sake::model::animation_controller::{AnimationController, Animation, AnimationFrame, AnimationCondition, PlayMode};
+
+//First atlas frame of each animation, the others follow in order
+const WALK: usize = 10;
+const DIVE: usize = 20;
+const SWAY: usize = 30;
+const STILL: usize = 40;
+const IDLE: usize = 50;
+const RUN: usize = 60;
+
+//Frames counted on from the first one, each shown for a second
+fn animation(first_frame: usize, frame_count: usize, mode: PlayMode) -> Animation
+{
+    Animation { frames: (0..frame_count).map(|i| AnimationFrame { frame: first_frame + i, duration: 1.0, event: None }).collect(), mode }
+}
+
+fn controller(animations: Vec<(&'static str, Animation)>) -> AnimationController
+{
+    let start_animation = animations[0].0;
+    AnimationController::new(animations.into_iter().collect(), start_animation).unwrap()
+}
+
+fn frames_over_time(controller: &mut AnimationController, seconds: usize) -> Vec<usize>
+{
+    (0..seconds).map(|_|
+    {
+        controller.update(1.0);
+        controller.get_frame()
+    }).collect()
+}
+
+#[test]
+fn loops_start_over_after_the_last_frame()
+{
+    let mut controller = controller(vec![ ("Walk", animation(WALK, 3, PlayMode::Loop)) ]);
+
+    assert_eq!(controller.get_frame(), WALK);
+    assert_eq!(frames_over_time(&mut controller, 4), vec![ WALK + 1, WALK + 2, WALK, WALK + 1 ]);
+}
+
+#[test]
+fn one_shots_stop_on_the_last_frame()
+{
+    let mut controller = controller(vec![ ("Dive", animation(DIVE, 3, PlayMode::OneShot)) ]);
+
+    assert!(controller.is_playing_one_shot());
+    assert_eq!(frames_over_time(&mut controller, 4), vec![ DIVE + 1, DIVE + 2, DIVE + 2, DIVE + 2 ]);
+    assert!(!controller.is_playing_one_shot());
+}
+
+#[test]
+fn ping_pongs_turn_around_at_both_ends()
+{
+    let mut controller = controller(vec![ ("Sway", animation(SWAY, 3, PlayMode::PingPong)), ("Still", animation(STILL, 1, PlayMode::PingPong)) ]);
+
+    assert_eq!(frames_over_time(&mut controller, 5), vec![ SWAY + 1, SWAY + 2, SWAY + 1, SWAY, SWAY + 1 ]);
+
+    controller.play("Still").unwrap();
+    assert_eq!(frames_over_time(&mut controller, 2), vec![ STILL, STILL ]);
+}
+
+#[test]
+fn long_updates_skip_frames()
+{
+    let mut controller = controller(vec![ ("Walk", animation(WALK, 3, PlayMode::Loop)) ]);
+    controller.update(2.5);
+    assert_eq!(controller.get_frame(), WALK + 2);
+}
+
+#[test]
+fn only_the_given_animations_are_scaled()
+{
+    let mut controller = controller(vec![ ("Walk", animation(WALK, 3, PlayMode::Loop)), ("Still", animation(STILL, 2, PlayMode::Loop)) ]);
+    controller.scale_durations(&[ "Still" ], 2.0);
+
+    assert_eq!(frames_over_time(&mut controller, 2), vec![ WALK + 1, WALK + 2 ]);
+
+    controller.play("Still").unwrap();
+    assert_eq!(frames_over_time(&mut controller, 4), vec![ STILL, STILL + 1, STILL + 1, STILL ]);
+}
+
+fn fox_like_controller() -> AnimationController
+{
+    let mut controller = controller(vec![
+        ("Idle", animation(IDLE, 2, PlayMode::Loop)),
+        ("Run", animation(RUN, 2, PlayMode::Loop)),
+        ("Dive", animation(DIVE, 2, PlayMode::OneShot)),
+    ]);
+    controller.add_transition("Idle", "Run", AnimationCondition::Parameter("Running", true)).unwrap();
+    controller.add_transition("Run", "Idle", AnimationCondition::Parameter("Running", false)).unwrap();
+    controller.add_transition("Idle", "Dive", AnimationCondition::Trigger("Dive")).unwrap();
+    controller.add_transition("Dive", "Idle", AnimationCondition::Finished).unwrap();
+    controller
+}
+
+#[test]
+fn parameters_switch_animations_while_they_are_set()
+{
+    let mut controller = fox_like_controller();
+
+    controller.set_parameter("Running", true);
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Run");
+    assert_eq!(controller.get_frame(), RUN);
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Run");
+
+    controller.set_parameter("Running", false);
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Idle");
+}
+
+#[test]
+fn triggers_only_count_for_the_next_update()
+{
+    let mut controller = fox_like_controller();
+
+    //Running has no transition to the dive, so the trigger is gone once the fox stops
+    controller.set_parameter("Running", true);
+    controller.update(0.1);
+    controller.trigger("Dive");
+    controller.update(0.1);
+    controller.set_parameter("Running", false);
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Idle");
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Idle");
+
+    controller.trigger("Dive");
+    controller.update(0.1);
+    assert_eq!(controller.get_current_animation(), "Dive");
+}
+
+#[test]
+fn finished_one_shots_move_on()
+{
+    let mut controller = fox_like_controller();
+    controller.trigger("Dive");
+    controller.update(0.1);
+
+    controller.update(1.0);
+    assert_eq!(controller.get_current_animation(), "Dive");
+    controller.update(1.0);
+    assert_eq!(controller.get_current_animation(), "Idle");
+    assert_eq!(controller.get_frame(), IDLE);
+}
+
+#[test]
+fn events_fire_when_their_frame_is_entered()
+{
+    let mut controller = controller(vec![
+        ("Walk", animation(WALK, 2, PlayMode::Loop).with_event(1, "footstep")),
+        ("Dive", animation(DIVE, 2, PlayMode::OneShot).with_event(0, "dive")),
+    ]);
+
+    controller.update(0.5);
+    assert!(controller.take_events().is_empty());
+    controller.update(0.5);
+    assert_eq!(controller.take_events(), vec![ "footstep" ]);
+    assert!(controller.take_events().is_empty());
+
+    //Every frame that is passed counts
+    controller.update(4.0);
+    assert_eq!(controller.take_events(), vec![ "footstep", "footstep" ]);
+
+    //Playing enters the first frame
+    controller.play("Dive").unwrap();
+    assert_eq!(controller.take_events(), vec![ "dive" ]);
+}
+
+#[test]
+fn events_beyond_the_last_frame_go_on_the_last_one()
+{
+    let animation = animation(DIVE, 2, PlayMode::OneShot).with_event(5, "dive");
+    assert_eq!(animation.frames[1].event, Some("dive"));
+}
+
+#[test]
+fn unknown_animations_are_an_error()
+{
+    let empty = vec![ ("Walk", animation(WALK, 1, PlayMode::Loop)), ("Empty", animation(STILL, 0, PlayMode::Loop)) ];
+    assert!(AnimationController::new(empty.into_iter().collect(), "Walk").is_err());
+    assert!(AnimationController::new(vec![ ("Walk", animation(WALK, 1, PlayMode::Loop)) ].into_iter().collect(), "Run").is_err());
+
+    let mut controller = fox_like_controller();
+    assert!(controller.add_transition("Idle", "Jump", AnimationCondition::Finished).is_err());
+    assert!(controller.add_transition("Jump", "Idle", AnimationCondition::Finished).is_err());
+    assert!(controller.play("Jump").is_err());
+    assert_eq!(controller.get_current_animation(), "Idle");
+}
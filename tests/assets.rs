@@ -0,0 +1,94 @@
+//! Native tests of the asset manifest and of the store that collects the assets handed over by the page.
+
+use for_fox_sake::assets::{AssetManifest, AssetStore, AssetKind};
+
+const MANIFEST: &str = "
+    tile_map tiles tiles.bmp          # startup section
+    sprite_tile_map sprites sprites.bmp
+    sound bark bark.ogg
+
+    level campaign_1
+    tile_map night_tiles night.bmp
+    config night_config night.txt
+";
+
+#[test]
+fn manifest_entries_belong_to_the_section_above_them()
+{
+    let manifest = AssetManifest::parse(MANIFEST).unwrap();
+
+    let names: Vec<&str> = manifest.get_entries_for("campaign_0").iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, vec![ "tiles", "sprites", "bark" ]);
+    let night_tiles = manifest.get_entry("night_tiles").unwrap();
+    assert_eq!(night_tiles.level_key.as_deref(), Some("campaign_1"));
+    assert_eq!(night_tiles.file, "night.bmp");
+    assert_eq!(manifest.get_entries_for("campaign_1").len(), 5);
+}
+
+#[test]
+fn invalid_manifests_are_an_error()
+{
+    assert!(AssetManifest::parse("tile_map tiles tiles.bmp").is_err());
+    assert!(AssetManifest::parse("tile_map tiles a.bmp\nsprite_tile_map sprites b.bmp\ntile_map tiles c.bmp").is_err());
+    assert!(AssetManifest::parse("tile_map tiles a.bmp\nsprite_tile_map sprites b.bmp\nmusic song c.ogg").is_err());
+    assert!(AssetManifest::parse("tile_map tiles a.bmp\nsprite_tile_map sprites b.bmp\nlevel").is_err());
+}
+
+#[test]
+fn progress_counts_the_assets_of_the_startup_section_and_the_level()
+{
+    let mut store = AssetStore::new(AssetManifest::parse(MANIFEST).unwrap());
+    assert_eq!(store.get_progress("campaign_0"), 0.0);
+
+    store.provide("tiles", vec![ 1 ]).unwrap();
+    store.provide("sprites", vec![ 2 ]).unwrap();
+    assert!((store.get_progress("campaign_0") - 2.0 / 3.0).abs() < 1e-6);
+    assert!(!store.is_complete("campaign_0"));
+
+    store.provide("bark", vec![ 3 ]).unwrap();
+    assert!(store.is_complete("campaign_0"));
+    assert!(!store.is_complete("campaign_1"));
+    assert!((store.get_progress("campaign_1") - 0.6).abs() < 1e-6);
+    let missing: Vec<&str> = store.get_missing("campaign_1").iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(missing, vec![ "night_tiles", "night_config" ]);
+}
+
+#[test]
+fn assets_missing_in_the_manifest_are_an_error()
+{
+    let mut store = AssetStore::new(AssetManifest::parse(MANIFEST).unwrap());
+    assert!(store.provide("music", vec![]).is_err());
+}
+
+#[test]
+fn assets_of_a_level_replace_those_of_the_startup_section()
+{
+    let mut store = AssetStore::new(AssetManifest::parse(MANIFEST).unwrap());
+    store.provide("tiles", vec![ 1 ]).unwrap();
+    store.provide("night_tiles", vec![ 2 ]).unwrap();
+
+    assert_eq!(store.get(AssetKind::TileMap, "campaign_0"), Some(&[ 1u8 ][..]));
+    assert_eq!(store.get(AssetKind::TileMap, "campaign_1"), Some(&[ 2u8 ][..]));
+    assert_eq!(store.get(AssetKind::Config, "campaign_0"), None);
+}
+
+#[test]
+fn level_assets_are_decoded()
+{
+    let mut store = AssetStore::new(AssetManifest::parse(MANIFEST).unwrap());
+    store.provide("tiles", include_bytes!("../resources/testTileMap.bmp").to_vec()).unwrap();
+    store.provide("sprites", include_bytes!("../resources/spriteTileMap.bmp").to_vec()).unwrap();
+    store.provide("night_tiles", include_bytes!("../resources/spriteTileMap.bmp").to_vec()).unwrap();
+    store.provide("night_config", b"lives = 5".to_vec()).unwrap();
+
+    let day = store.get_level_assets("campaign_0").unwrap();
+    assert!(day.config_text.is_none());
+    assert!(day.sprite_atlas_descriptor.is_none());
+
+    let night = store.get_level_assets("campaign_1").unwrap();
+    assert_eq!(night.tile_map.dimensions(), night.sprite_tile_map.dimensions());
+    assert_eq!(night.config_text.as_deref(), Some("lives = 5"));
+
+    store.provide("night_tiles", vec![ 0, 1, 2 ]).unwrap();
+    assert!(store.get_level_assets("campaign_1").is_err());
+}
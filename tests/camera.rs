@@ -0,0 +1,101 @@
+//! Native tests of the camera that follows the fox and of the screen shake.
+
+use for_fox_sake::model::camera::Camera;
+use for_fox_sake::model::game_config::{GameConfig, Difficulty};
+use for_fox_sake::model::screen_shake::ScreenShake;
+
+fn config(zoom: f32, smoothing: f32) -> GameConfig
+{
+    GameConfig { camera_zoom: zoom, camera_dead_zone_x: 0.2, camera_dead_zone_y: 0.2, camera_smoothing: smoothing, ..GameConfig::from_difficulty(Difficulty::Normal) }
+}
+
+fn at(x: f32, y: f32) -> cgmath::Vector2<f32>
+{
+    cgmath::Vector2 { x, y }
+}
+
+#[test]
+fn the_whole_level_fits_at_zoom_one()
+{
+    let mut camera = Camera::new();
+    camera.update(at(0.8, -0.8), &config(1.0, 0.0), 0.1);
+    assert_eq!(camera.position, at(0.0, 0.0));
+}
+
+#[test]
+fn the_camera_waits_until_the_target_leaves_the_dead_zone()
+{
+    let mut camera = Camera::new();
+    camera.snap_to(at(0.0, 0.0), &config(2.0, 0.0));
+
+    //The dead zone of 0.2 screen units is 0.1 in the level at zoom 2
+    camera.update(at(0.05, 0.0), &config(2.0, 0.0), 0.1);
+    assert_eq!(camera.position, at(0.0, 0.0));
+
+    camera.update(at(0.3, 0.0), &config(2.0, 0.0), 0.1);
+    assert!((camera.position.x - 0.2).abs() < 0.0001);
+}
+
+#[test]
+fn smoothing_follows_part_of_the_way()
+{
+    let mut camera = Camera::new();
+    camera.snap_to(at(0.0, 0.0), &config(2.0, 0.5));
+    camera.update(at(0.4, 0.0), &config(2.0, 0.5), 0.1);
+
+    assert!(camera.position.x > 0.0 && camera.position.x < 0.3);
+}
+
+#[test]
+fn the_camera_stays_inside_the_level()
+{
+    let mut camera = Camera::new();
+    camera.snap_to(at(1.0, -1.0), &config(2.0, 0.0));
+
+    //Half of the level is on screen, so the center can move up to 0.5 from the middle
+    assert_eq!(camera.to_view_model(&config(2.0, 0.0)).position, at(0.5, -0.5));
+    assert_eq!(camera.to_view_model(&config(2.0, 0.0)).zoom, 2.0);
+}
+
+fn shake_after(seed: u32, ticks: u32) -> (cgmath::Vector2<f32>, f32)
+{
+    let config = config(1.0, 0.0);
+    let mut shake = ScreenShake::new(seed);
+    shake.add_trauma(1.0);
+    for _ in 0..ticks
+    {
+        shake.update(&config, 1.0 / 60.0);
+    }
+    shake.get_shake(&config)
+}
+
+#[test]
+fn the_same_seed_shakes_the_same_way()
+{
+    assert_eq!(shake_after(7, 10), shake_after(7, 10));
+    assert_ne!(shake_after(7, 10), shake_after(8, 10));
+}
+
+#[test]
+fn trauma_decays_until_the_shake_stops()
+{
+    let (offset, rotation) = shake_after(7, 10);
+    assert!(offset.x != 0.0 || offset.y != 0.0 || rotation != 0.0);
+
+    //Trauma decays by 1.5 per second
+    assert_eq!(shake_after(7, 60), (at(0.0, 0.0), 0.0));
+}
+
+#[test]
+fn disabled_shake_leaves_the_camera_still()
+{
+    let config = config(1.0, 0.0);
+    let mut camera = Camera::new();
+    camera.add_trauma(1.0);
+    camera.update(at(0.0, 0.0), &config, 0.1);
+    camera.set_shake_enabled(false);
+
+    let view_model = camera.to_view_model(&config);
+    assert_eq!((view_model.position, view_model.rotation), (at(0.0, 0.0), 0.0));
+    assert!(camera.get_trauma() > 0.0);
+}
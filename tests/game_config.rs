@@ -0,0 +1,94 @@
+//! Native tests of the game config text format.
+
+use for_fox_sake::model::game_config::{GameConfig, Difficulty};
+
+fn normal() -> GameConfig
+{
+    GameConfig::from_difficulty(Difficulty::Normal)
+}
+
+#[test]
+fn the_shipped_config_keeps_the_chosen_difficulty()
+{
+    for difficulty in [ Difficulty::Easy, Difficulty::Normal, Difficulty::Hard ]
+    {
+        let preset = GameConfig::from_difficulty(difficulty);
+        let config = GameConfig::parse(include_str!("../resources/gameConfig.txt"), preset.clone()).unwrap();
+
+        assert_eq!(config.fox_speed, preset.fox_speed);
+        assert_eq!(config.lives, preset.lives);
+        assert_eq!(config.wolf_reaction_time, preset.wolf_reaction_time);
+    }
+}
+
+#[test]
+fn settings_that_are_left_out_keep_their_value()
+{
+    let config = GameConfig::parse("fox_speed = 0.5   # faster\n\n# lives stay\nwave_bonus=7", normal()).unwrap();
+
+    assert_eq!(config.fox_speed, 0.5);
+    assert_eq!(config.wave_bonus, 7);
+    assert_eq!(config.lives, normal().lives);
+    assert_eq!(config.wolf_speed, normal().wolf_speed);
+}
+
+#[test]
+fn a_difficulty_line_resets_everything_before_it()
+{
+    let config = GameConfig::parse("fox_speed = 0.5\ndifficulty = hard\nlives = 9", normal()).unwrap();
+
+    assert_eq!(config.fox_speed, GameConfig::from_difficulty(Difficulty::Hard).fox_speed);
+    assert_eq!(config.lives, 9);
+}
+
+#[test]
+fn unknown_settings_are_an_error()
+{
+    let err = GameConfig::parse("fox_speed = 0.5\nfox_sped = 0.5", normal()).err().unwrap();
+    assert!(err.starts_with("Line 2"), "{}", err);
+    assert!(GameConfig::parse("difficulty = impossible", normal()).is_err());
+}
+
+#[test]
+fn bad_values_are_an_error()
+{
+    assert!(GameConfig::parse("fox_speed = fast", normal()).is_err());
+    assert!(GameConfig::parse("lives = -1", normal()).is_err());
+    assert!(GameConfig::parse("fox_speed 0.5", normal()).is_err());
+}
+
+#[test]
+fn times_the_model_divides_by_have_to_be_positive()
+{
+    assert!(GameConfig::parse("fox_animation_frame_time = 0", normal()).is_err());
+    assert!(GameConfig::parse("hole_vignette_duration = -1.5", normal()).is_err());
+    assert!(GameConfig::parse("hole_particles_lifetime = NaN", normal()).is_err());
+    assert!(GameConfig::parse("invulnerability_time = -0.1", normal()).is_err());
+
+    assert!(GameConfig::parse("knockback_time = 0\ncamera_smoothing = 0", normal()).is_ok());
+}
+
+#[test]
+fn hit_points_sizes_and_radii_have_to_be_positive()
+{
+    assert!(GameConfig::parse("max_hit_points = 0", normal()).is_err());
+    assert!(GameConfig::parse("sprite_size = 0", normal()).is_err());
+    assert!(GameConfig::parse("collect_radius = -0.1", normal()).is_err());
+    assert!(GameConfig::parse("wolf_bite_radius = 0", normal()).is_err());
+    assert!(GameConfig::parse("screen_shake_decay = -1", normal()).is_err());
+
+    assert!(GameConfig::parse("max_hit_points = 1\nwolf_detection_range = 0\nscreen_shake_decay = 0", normal()).is_ok());
+}
+
+#[test]
+fn trauma_and_zoom_have_to_be_in_range()
+{
+    assert!(GameConfig::parse("bite_trauma = 1.5", normal()).is_err());
+    assert!(GameConfig::parse("dive_trauma = -0.1", normal()).is_err());
+    assert!(GameConfig::parse("camera_zoom = 0.5", normal()).is_err());
+    assert!(GameConfig::parse("camera_zoom = 100", normal()).is_err());
+    assert!(GameConfig::parse("camera_dead_zone_x = 1.5", normal()).is_err());
+
+    let config = GameConfig::parse("bite_trauma = 1\ndive_trauma = 0\ncamera_zoom = 2.5", normal()).unwrap();
+    assert_eq!(config.camera_zoom, 2.5);
+}
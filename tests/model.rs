@@ -0,0 +1,231 @@
+//! Native tests of the campaign rules: collecting items, completing a level and the rank it earns.
+
+use for_fox_sake::for_fox_sake::input::Input;
+use for_fox_sake::for_fox_sake::read_only_input::ReadOnlyInput;
+use for_fox_sake::model::Model;
+use for_fox_sake::model::game_mode::GameMode;
+use for_fox_sake::model::game_config::{GameConfig, Difficulty};
+use for_fox_sake::model::rank::{Rank, RankThresholds};
+use for_fox_sake::model::speedrun::SpeedrunTimer;
+use for_fox_sake::sprite_atlas::SpriteAtlas;
+
+const KEY_RIGHT: i32 = 39;
+
+fn sprite_atlas() -> SpriteAtlas
+{
+    SpriteAtlas::parse(include_str!("../resources/spriteTileMap.atlas")).unwrap()
+}
+
+fn campaign_model(config: GameConfig) -> Model
+{
+    let mut model = Model::new(GameMode::Campaign, config, &sprite_atlas()).unwrap();
+    model.load_level(0).unwrap();
+    model
+}
+
+fn tick(model: &mut Model, input: &mut Input)
+{
+    model.update(ReadOnlyInput::new(input), Model::TICK_DURATION).unwrap();
+    input.finalize();
+}
+
+#[test]
+fn items_add_their_points_when_the_fox_reaches_them()
+{
+    let mut model = campaign_model(GameConfig::from_difficulty(Difficulty::Normal));
+    let mut input = Input::new();
+
+    //The egg of the first level is two tiles right of the start
+    input.key_down(KEY_RIGHT);
+    for _ in 0..1000
+    {
+        if model.get_score() > 0
+        {
+            break;
+        }
+        tick(&mut model, &mut input);
+    }
+
+    assert_eq!(model.get_score(), 25);
+    assert!(!model.is_level_completed());
+    assert!(model.get_rank().is_none());
+}
+
+#[test]
+fn collecting_every_item_completes_the_level_with_its_bonuses()
+{
+    //Everything is in reach from the start
+    let config = GameConfig { collect_radius: 10.0, ..GameConfig::from_difficulty(Difficulty::Normal) };
+    let mut model = campaign_model(config.clone());
+    let mut input = Input::new();
+    tick(&mut model, &mut input);
+
+    let speed_bonus = ((20.0 - Model::TICK_DURATION) * config.speed_bonus_per_second) as u32;
+    assert!(model.is_level_completed());
+    assert_eq!(model.get_score(), 10 + 10 + 25 + speed_bonus + config.no_holes_bonus);
+    assert!(model.get_rank() == Some(Rank::Gold));
+
+    //Nothing changes after the level is completed
+    tick(&mut model, &mut input);
+    assert_eq!(model.get_score(), 10 + 10 + 25 + speed_bonus + config.no_holes_bonus);
+}
+
+#[test]
+fn ranks_start_at_their_threshold()
+{
+    let thresholds = RankThresholds { bronze: 10, silver: 20, gold: 30 };
+
+    assert!(thresholds.get_rank(9).is_none());
+    assert!(thresholds.get_rank(10) == Some(Rank::Bronze));
+    assert!(thresholds.get_rank(19) == Some(Rank::Bronze));
+    assert!(thresholds.get_rank(20) == Some(Rank::Silver));
+    assert!(thresholds.get_rank(30) == Some(Rank::Gold));
+    assert!(thresholds.get_rank(u32::MAX) == Some(Rank::Gold));
+}
+
+#[test]
+fn splits_and_the_finished_run_count_from_the_start_of_the_level()
+{
+    let mut model = campaign_model(GameConfig::from_difficulty(Difficulty::Normal));
+    let mut input = Input::new();
+    for _ in 0..30
+    {
+        tick(&mut model, &mut input);
+    }
+
+    //Restart with everything in reach, the level is completed in its first tick
+    model.set_config(GameConfig { collect_radius: 10.0, ..GameConfig::from_difficulty(Difficulty::Normal) });
+    model.load_level(0).unwrap();
+    tick(&mut model, &mut input);
+
+    assert!(model.is_level_completed());
+    assert_eq!(model.get_run_ticks(), 31);
+    assert_eq!(model.get_splits(), &vec![ 1 ]);
+    assert_eq!(model.get_finished_run().ticks, 1);
+    assert_eq!(model.get_finished_run().splits, vec![ 1 ]);
+    assert_eq!(model.get_finished_run().ghost.len(), 1);
+}
+
+#[test]
+fn splits_are_kept_for_every_level_of_the_run()
+{
+    let mut model = campaign_model(GameConfig { collect_radius: 10.0, ..GameConfig::from_difficulty(Difficulty::Normal) });
+    let mut input = Input::new();
+    tick(&mut model, &mut input);
+
+    model.load_level(1).unwrap();
+    tick(&mut model, &mut input);
+
+    assert!(model.is_level_completed());
+    assert_eq!(model.get_splits(), &vec![ 1, 1 ]);
+    assert_eq!(model.get_run_ticks(), 2);
+}
+
+#[test]
+fn ghost_recordings_stop_growing_at_their_maximum_length()
+{
+    let mut timer = SpeedrunTimer::new();
+    for _ in 0..SpeedrunTimer::MAX_RECORDING_LENGTH + 10
+    {
+        timer.tick();
+        timer.record(cgmath::Vector2 { x: 0.0, y: 0.0 });
+    }
+
+    assert_eq!(timer.recording.len(), SpeedrunTimer::MAX_RECORDING_LENGTH);
+}
+
+fn wolf_positions(model: &Model) -> Vec<cgmath::Vector2<f32>>
+{
+    let wolf = sprite_atlas().get_frame_index("wolf").unwrap();
+    let sprites = model.to_sprites_view_model().unwrap();
+    (0..sprites.get_count()).filter(|i| sprites.frames[*i] == wolf).map(|i| sprites.positions[i]).collect()
+}
+
+fn wolf_positions_after_a_second(config: GameConfig) -> (Vec<cgmath::Vector2<f32>>, Vec<cgmath::Vector2<f32>>)
+{
+    let mut model = campaign_model(config);
+    let mut input = Input::new();
+    let start = wolf_positions(&model);
+    for _ in 0..60
+    {
+        tick(&mut model, &mut input);
+    }
+    (start, wolf_positions(&model))
+}
+
+#[test]
+fn wolves_placed_in_the_campaign_hunt_within_the_detection_range_of_the_preset()
+{
+    //The wolf of the first level is only within the detection range of the hard preset
+    let (start, end) = wolf_positions_after_a_second(GameConfig::from_difficulty(Difficulty::Normal));
+    assert_eq!(start, end);
+
+    let (start, end) = wolf_positions_after_a_second(GameConfig::from_difficulty(Difficulty::Hard));
+    assert_eq!(start.len(), 1);
+    assert!(end[0].y > start[0].y);
+}
+
+#[test]
+fn wolves_placed_in_the_campaign_wait_for_their_reaction_time()
+{
+    let (start, end) = wolf_positions_after_a_second(GameConfig { wolf_reaction_time: 1.5, ..GameConfig::from_difficulty(Difficulty::Hard) });
+    assert_eq!(start, end);
+}
+
+#[test]
+fn wolves_placed_in_the_campaign_stand_still_when_the_config_says_so()
+{
+    let (start, end) = wolf_positions_after_a_second(GameConfig { placed_wolves_hunt: false, ..GameConfig::from_difficulty(Difficulty::Hard) });
+    assert_eq!(start, end);
+}
+
+#[test]
+fn a_lethal_bite_is_only_reported_as_death()
+{
+    let config = GameConfig { max_hit_points: 2, lives: 1, ..GameConfig::from_difficulty(Difficulty::Hard) };
+    let mut model = campaign_model(config);
+    let mut input = Input::new();
+
+    let mut events: Vec<&'static str> = vec![];
+    for _ in 0..3000
+    {
+        tick(&mut model, &mut input);
+        events.extend(model.take_events().iter().map(|x| x.get_name()).filter(|x| *x != "footstep"));
+        if events.contains(&"GameOver")
+        {
+            break;
+        }
+    }
+
+    assert_eq!(events, vec![ "FoxDamaged", "FoxDied", "GameOver" ]);
+}
+
+#[test]
+fn a_new_run_after_game_over_starts_with_all_lives()
+{
+    let config = GameConfig { max_hit_points: 1, lives: 1, ..GameConfig::from_difficulty(Difficulty::Hard) };
+    let mut model = campaign_model(config);
+    let mut input = Input::new();
+    for _ in 0..3000
+    {
+        tick(&mut model, &mut input);
+        if model.is_game_over()
+        {
+            break;
+        }
+    }
+    assert!(model.is_game_over());
+    assert_eq!(model.get_lives(), 0);
+
+    model.start_run();
+    model.load_level(0).unwrap();
+    assert!(!model.is_game_over());
+    assert_eq!(model.get_lives(), 1);
+    assert_eq!(model.get_hit_points(), 1);
+    assert_eq!(model.get_score(), 0);
+    assert_eq!(model.get_run_ticks(), 0);
+
+    //The fox is simulated again
+    tick(&mut model, &mut input);
+    assert_eq!(model.get_run_ticks(), 1);
+}
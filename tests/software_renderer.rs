@@ -0,0 +1,227 @@
+//! Native tests of the software renderer, with tile maps of one solid color per tile.
+
+use for_fox_sake::sprite_atlas::SpriteAtlas;
+use for_fox_sake::view::software_renderer::SoftwareRenderer;
+use for_fox_sake::view_models::{LevelViewModel, CameraViewModel, SpritesViewModel, ParticlesViewModel, LightsViewModel, PostProcessViewModel, PostProcessEffect, PostProcessEffects, PostProcessParameters};
+
+const RED: image::Rgba<u8> = image::Rgba([255, 0, 0, 255]);
+const GREEN: image::Rgba<u8> = image::Rgba([0, 255, 0, 255]);
+const BLUE: image::Rgba<u8> = image::Rgba([0, 0, 255, 255]);
+const WHITE: image::Rgba<u8> = image::Rgba([255, 255, 255, 255]);
+
+//Tiles 0 to 3 are red, green, blue and white, counted row by row
+fn colored_tile_map() -> image::RgbaImage
+{
+    image::RgbaImage::from_fn(64, 64, |x, y| match (x < 32, y < 32)
+    {
+        (true, true) => RED,
+        (false, true) => GREEN,
+        (true, false) => BLUE,
+        (false, false) => WHITE,
+    })
+}
+
+fn create_renderer() -> SoftwareRenderer
+{
+    let atlas = SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell green 1 0").unwrap();
+    SoftwareRenderer::new(colored_tile_map(), &atlas, colored_tile_map(), atlas.clone(), 40, 40).unwrap()
+}
+
+fn sprites(frames: &[usize], alpha: f32) -> SpritesViewModel
+{
+    SpritesViewModel {
+        sizes: vec![ cgmath::Vector2 { x: 1.0, y: 1.0 }; frames.len() ],
+        positions: vec![ cgmath::Vector2 { x: 0.0, y: 0.0 }; frames.len() ],
+        frames: frames.to_vec(),
+        alphas: vec![ alpha; frames.len() ],
+    }
+}
+
+fn no_particles() -> ParticlesViewModel
+{
+    ParticlesViewModel {
+        positions: vec![],
+        max_speeds: vec![],
+        running_times: vec![],
+        max_running_times: vec![],
+    }
+}
+
+fn whole_level() -> CameraViewModel
+{
+    CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }
+}
+
+#[test]
+fn background_tiles_are_taken_row_by_row_from_the_top_left()
+{
+    let level = LevelViewModel { data: vec![ 0, 1, 2, 3 ], width: 2.0, height: 2.0 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &whole_level()).unwrap();
+
+    assert_eq!(*image.get_pixel(10, 10), RED);
+    assert_eq!(*image.get_pixel(30, 10), GREEN);
+    assert_eq!(*image.get_pixel(10, 30), BLUE);
+    assert_eq!(*image.get_pixel(30, 30), WHITE);
+}
+
+#[test]
+fn background_tiles_follow_the_grid_of_the_tile_atlas()
+{
+    //A 3x3 tile map like the ones of pack_atlas, the tiles are red, green and blue in the first row and white everywhere else
+    let colors = [ RED, GREEN, BLUE ];
+    let tile_map = image::RgbaImage::from_fn(48, 48, |x, y| if y < 16 { colors[(x / 16) as usize] } else { WHITE });
+    let tile_atlas = SpriteAtlas::parse("size 48 48\ngrid 3 3").unwrap();
+    let sprite_atlas = SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell green 1 0").unwrap();
+    let renderer = SoftwareRenderer::new(tile_map, &tile_atlas, colored_tile_map(), sprite_atlas, 40, 40).unwrap();
+
+    let level = LevelViewModel { data: vec![ 2, 1, 4, 0 ], width: 2.0, height: 2.0 };
+    let image = renderer.render(&level, &sprites(&[], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &whole_level()).unwrap();
+
+    assert_eq!(*image.get_pixel(10, 10), BLUE);
+    assert_eq!(*image.get_pixel(30, 10), GREEN);
+    assert_eq!(*image.get_pixel(10, 30), WHITE);
+    assert_eq!(*image.get_pixel(30, 30), RED);
+}
+
+#[test]
+fn sprites_are_blended_with_their_alpha()
+{
+    let level = LevelViewModel { data: vec![ 0 ], width: 1.0, height: 1.0 };
+    let image = create_renderer().render(&level, &sprites(&[0], 0.5), &no_particles(), &PostProcessViewModel { effects: vec![] }, &whole_level()).unwrap();
+
+    //The sprite covers the center half of the screen. Like in WebGL the alpha channel is blended as well.
+    assert_eq!(*image.get_pixel(20, 20), image::Rgba([128, 128, 0, 191]));
+    assert_eq!(*image.get_pixel(2, 2), RED);
+}
+
+#[test]
+fn unknown_sprites_are_an_error()
+{
+    let level = LevelViewModel { data: vec![ 0 ], width: 1.0, height: 1.0 };
+    assert!(create_renderer().render(&level, &sprites(&[1], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &whole_level()).is_err());
+}
+
+#[test]
+fn the_camera_zooms_into_the_level()
+{
+    let level = LevelViewModel { data: vec![ 0, 1, 2, 3 ], width: 2.0, height: 2.0 };
+    let top_left = CameraViewModel { position: cgmath::Vector2 { x: -0.5, y: 0.5 }, zoom: 2.0, rotation: 0.0 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &top_left).unwrap();
+
+    assert_eq!(*image.get_pixel(5, 5), RED);
+    assert_eq!(*image.get_pixel(34, 34), RED);
+}
+
+#[test]
+fn the_camera_turns_the_level()
+{
+    let level = LevelViewModel { data: vec![ 0, 1, 2, 3 ], width: 2.0, height: 2.0 };
+    let turned = CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: std::f32::consts::FRAC_PI_2 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &turned).unwrap();
+
+    //Turned counterclockwise, the top right tile ends up in the top left
+    assert_eq!(*image.get_pixel(5, 5), GREEN);
+    assert_eq!(*image.get_pixel(34, 34), BLUE);
+}
+
+#[test]
+fn cells_around_the_map_are_drawn_as_tile_0()
+{
+    let level = LevelViewModel { data: vec![ 3 ], width: 1.0, height: 1.0 };
+    let shaken = CameraViewModel { position: cgmath::Vector2 { x: 0.1, y: -0.1 }, zoom: 0.8, rotation: 0.05 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &PostProcessViewModel { effects: vec![] }, &shaken).unwrap();
+
+    assert_eq!(*image.get_pixel(20, 20), WHITE);
+    assert_eq!(*image.get_pixel(0, 0), RED);
+}
+
+//The effect at its start, with the look it has for events
+fn started(name: PostProcessEffects) -> PostProcessViewModel
+{
+    PostProcessViewModel { effects: vec![ PostProcessEffect { name, running_time: 0.0, max_running_time: 1.0, parameters: PostProcessParameters::default_for(name) } ] }
+}
+
+#[test]
+fn desaturate_turns_the_scene_grey()
+{
+    let level = LevelViewModel { data: vec![ 0, 1, 2, 3 ], width: 2.0, height: 2.0 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &started(PostProcessEffects::DESATURATE), &whole_level()).unwrap();
+
+    assert_eq!(*image.get_pixel(10, 10), image::Rgba([76, 76, 76, 255]));
+    assert_eq!(*image.get_pixel(30, 10), image::Rgba([150, 150, 150, 255]));
+    assert_eq!(*image.get_pixel(30, 30), WHITE);
+}
+
+#[test]
+fn chromatic_aberration_pulls_red_and_blue_apart_at_the_edges()
+{
+    //Red and green columns of 10 pixels
+    let level = LevelViewModel { data: vec![ 0, 1, 0, 1 ], width: 4.0, height: 1.0 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &started(PostProcessEffects::CHROMATIC_ABERRATION), &whole_level()).unwrap();
+
+    //Inside the columns nothing changes, at the first green pixel the red is taken from closer to the edge
+    assert_eq!(*image.get_pixel(5, 20), RED);
+    let border = image.get_pixel(10, 20);
+    assert!(border[0] > 0);
+    assert_eq!(border[1], 255);
+}
+
+#[test]
+fn pixelate_dissolves_into_the_color()
+{
+    let level = LevelViewModel { data: vec![ 3 ], width: 1.0, height: 1.0 };
+    let mut effects = started(PostProcessEffects::PIXELATE);
+    effects.effects[0].running_time = 1.0;
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &effects, &whole_level()).unwrap();
+
+    assert_eq!(*image.get_pixel(20, 20), image::Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn crt_darkens_every_other_row_and_cuts_off_the_corners()
+{
+    let level = LevelViewModel { data: vec![ 3 ], width: 1.0, height: 1.0 };
+    let image = create_renderer().render(&level, &sprites(&[], 1.0), &no_particles(), &started(PostProcessEffects::CRT), &whole_level()).unwrap();
+
+    assert_ne!(image.get_pixel(20, 19), image.get_pixel(20, 20));
+    assert_eq!(*image.get_pixel(0, 0), image::Rgba([0, 0, 0, 255]));
+}
+
+//White light in the center of the left tile of a map three tiles wide, in complete darkness
+fn light_on_the_left(radius: f32, occluding_tiles: Vec<i32>) -> LightsViewModel
+{
+    LightsViewModel {
+        ambient: cgmath::Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        positions: vec![ cgmath::Vector2 { x: -2.0 / 3.0, y: 0.0 } ],
+        radii: vec![ radius ],
+        colors: vec![ cgmath::Vector3 { x: 1.0, y: 1.0, z: 1.0 } ],
+        intensities: vec![ 1.0 ],
+        occluding_tiles,
+    }
+}
+
+#[test]
+fn light_fades_out_at_its_radius()
+{
+    let level = LevelViewModel { data: vec![ 3, 3, 3 ], width: 3.0, height: 1.0 };
+    let lights = light_on_the_left(1.5, vec![]);
+    let image = create_renderer().render_with_lights(&level, &sprites(&[], 1.0), &no_particles(), Some(&lights), &PostProcessViewModel { effects: vec![] }, &whole_level()).unwrap();
+
+    assert!(image.get_pixel(6, 20)[0] > 240);
+    assert_eq!(*image.get_pixel(34, 20), image::Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn walls_cast_shadows_but_are_lit_themselves()
+{
+    //White floor on both sides of a red wall
+    let level = LevelViewModel { data: vec![ 3, 0, 3 ], width: 3.0, height: 1.0 };
+    let render = |occluding_tiles: Vec<i32>| create_renderer().render_with_lights(&level, &sprites(&[], 1.0), &no_particles(), Some(&light_on_the_left(3.0, occluding_tiles)), &PostProcessViewModel { effects: vec![] }, &whole_level()).unwrap();
+
+    let shadowed = render(vec![ 0 ]);
+    assert!(shadowed.get_pixel(20, 20)[0] > 0);
+    assert_eq!(*shadowed.get_pixel(34, 20), image::Rgba([0, 0, 0, 255]));
+
+    let open = render(vec![]);
+    assert!(open.get_pixel(34, 20)[0] > 0);
+}
@@ -0,0 +1,557 @@
+//! Native tests of the view, drawn with the recording backend instead of WebGL2.
+
+use for_fox_sake::sprite_atlas::SpriteAtlas;
+use for_fox_sake::view::View;
+use for_fox_sake::view::scale_mode::{ScaleMode, Viewport};
+use for_fox_sake::view::texture_filter::TextureFilter;
+use for_fox_sake::view::render_backend::UniformValue;
+use for_fox_sake::view::transition::{Transition, TransitionKind};
+use for_fox_sake::view::recording_backend::{RecordingBackend, RenderCommand, RenderLog};
+use for_fox_sake::view_models::{LevelViewModel, CameraViewModel, SpritesViewModel, ParticlesViewModel, LightsViewModel, PostProcessViewModel, PostProcessEffect, PostProcessEffects, PostProcessParameters, Eased, Easing};
+
+fn create_view() -> (View, RenderLog)
+{
+    let atlas = SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 1 0").unwrap();
+    let backend = RecordingBackend::new();
+    let log = backend.get_log();
+    let view = View::new(Box::new(backend), image::RgbaImage::new(64, 64), atlas.clone(), image::RgbaImage::new(64, 64), atlas, 100, 100).unwrap();
+    (view, log)
+}
+
+fn foxes(count: usize) -> SpritesViewModel
+{
+    SpritesViewModel {
+        sizes: vec![ cgmath::Vector2 { x: 0.1, y: 0.1 }; count ],
+        positions: vec![ cgmath::Vector2 { x: 0.0, y: 0.0 }; count ],
+        frames: vec![ 0; count ],
+        alphas: vec![ 1.0; count ],
+    }
+}
+
+fn no_particles() -> ParticlesViewModel
+{
+    ParticlesViewModel {
+        positions: vec![],
+        max_speeds: vec![],
+        running_times: vec![],
+        max_running_times: vec![],
+    }
+}
+
+fn get_instance_data(log: &RenderLog) -> Vec<Vec<f32>>
+{
+    log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::UpdateInstanceBuffer { data, .. } => Some(data.clone()),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn frame_draws_background_sprites_particles_and_effects_then_scales_up_the_scene()
+{
+    let (mut view, log) = create_view();
+    let vignette = PostProcessEffect { name: PostProcessEffects::VIGNETTE, running_time: 0.0, max_running_time: 1.0, parameters: PostProcessParameters::default_for(PostProcessEffects::VIGNETTE) };
+    view.update(foxes(1), no_particles(), PostProcessViewModel { effects: vec![ vignette ] }, CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.1).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    let draws: Vec<(i32, i32)> = log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::DrawTriangles { vertex_count, .. } => Some((*vertex_count, 1)),
+        RenderCommand::DrawTrianglesInstanced { vertex_count, instance_count, .. } => Some((*vertex_count, *instance_count)),
+        _ => None,
+    }).collect();
+    assert!(matches!(log.borrow()[0], RenderCommand::BindRenderTarget { framebuffer: Some(_) }));
+    assert!(log.borrow().contains(&RenderCommand::BindRenderTarget { framebuffer: None }));
+    assert_eq!(draws, vec![ (6, 1), (6, 1), (6, 0), (6, 1), (6, 1) ]);
+}
+
+#[test]
+fn sprite_frames_are_uploaded_as_atlas_rects()
+{
+    let (mut view, log) = create_view();
+    view.update_sprites(foxes(1)).unwrap();
+
+    //Center, size, uv rect and alpha
+    assert_eq!(get_instance_data(&log), vec![ vec![ 0.0, 0.0, 0.1, 0.1, 0.5, 0.0, 1.0, 0.5, 1.0 ] ]);
+}
+
+#[test]
+fn sprite_tile_maps_have_to_divide_into_the_grid_of_the_atlas()
+{
+    let (mut view, log) = create_view();
+    let atlas = SpriteAtlas::parse("size 64 64\ngrid 4 4\ncell fox 0 0").unwrap();
+
+    assert!(view.set_sprite_tile_map(image::RgbaImage::new(66, 66), atlas.clone()).is_err());
+    view.update_sprites(foxes(1)).unwrap();
+    assert_eq!(get_instance_data(&log).last().unwrap()[4..8], [ 0.5, 0.0, 1.0, 0.5 ]);
+
+    view.set_sprite_tile_map(image::RgbaImage::new(32, 32), atlas).unwrap();
+    view.update_sprites(foxes(1)).unwrap();
+    assert_eq!(get_instance_data(&log).last().unwrap()[4..8], [ 0.0, 0.0, 0.25, 0.25 ]);
+}
+
+#[test]
+fn cells_outside_the_grid_are_an_error()
+{
+    assert!(SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 1 1").is_ok());
+    assert!(SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 2 0").is_err());
+    assert!(SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 0 -1").is_err());
+    assert!(SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 0.5 0").is_err());
+}
+
+#[test]
+fn frames_are_numbered_in_the_order_they_are_defined()
+{
+    let atlas = SpriteAtlas::parse("size 64 64\ngrid 2 2\ncell fox 1 0\ncell wolf 0 1\nanimation fox_run wolf fox wolf").unwrap();
+
+    assert_eq!(atlas.get_frame_index("wolf"), Ok(1));
+    assert_eq!(atlas.get_animation_frames("fox_run"), Ok(vec![ 1, 0, 1 ]));
+    assert_eq!(atlas.get_animation_frames("fox"), Ok(vec![ 0 ]));
+    assert!(atlas.get_frame_index("hole").is_err());
+    assert!(atlas.get_uv_rect(2).is_err());
+}
+
+#[test]
+fn thousands_of_sprites_are_drawn_in_one_call()
+{
+    let (mut view, log) = create_view();
+    view.update_sprites(foxes(5000)).unwrap();
+    assert_eq!(get_instance_data(&log)[0].len(), 5000 * 9);
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    assert!(log.borrow().contains(&RenderCommand::DrawTrianglesInstanced { first_vertex: 0, vertex_count: 6, instance_count: 5000 }));
+}
+
+fn map(width: u32, height: u32) -> LevelViewModel
+{
+    LevelViewModel { data: (0..(width * height) as i32).map(|x| x % 4).collect(), width: width as f32, height: height as f32 }
+}
+
+#[test]
+fn large_maps_are_uploaded_as_data_texture()
+{
+    let (mut view, log) = create_view();
+    log.borrow_mut().clear();
+    view.update_map(map(300, 200)).unwrap();
+
+    let created = log.borrow().iter().find_map(|command| match command
+    {
+        RenderCommand::CreateDataTexture { width, height, data, .. } => Some((*width, *height, data.len())),
+        _ => None,
+    });
+    assert_eq!(created, Some((300, 200, 300 * 200)));
+}
+
+#[test]
+fn maps_of_the_same_size_reuse_the_texture()
+{
+    let (mut view, log) = create_view();
+    view.update_map(map(30, 30)).unwrap();
+    log.borrow_mut().clear();
+
+    view.update_map(map(30, 30)).unwrap();
+
+    assert!(!log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateDataTexture { .. })));
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::UpdateDataTexture { width: 30, height: 30, .. })));
+}
+
+#[test]
+fn single_tiles_are_updated_in_place()
+{
+    let (mut view, log) = create_view();
+    view.update_map(map(30, 30)).unwrap();
+    log.borrow_mut().clear();
+
+    view.set_map_tile(4, 7, 3).unwrap();
+
+    assert_eq!(log.borrow().len(), 1);
+    assert!(matches!(&log.borrow()[0], RenderCommand::UpdateDataTexture { x: 4, y: 7, width: 1, height: 1, data, .. } if data == &vec![ 3 ]));
+    assert!(view.set_map_tile(30, 0, 3).is_err());
+}
+
+#[test]
+fn maps_with_missing_tiles_are_an_error()
+{
+    let (mut view, _) = create_view();
+    assert!(view.update_map(LevelViewModel { data: vec![ 0; 5 ], width: 3.0, height: 2.0 }).is_err());
+}
+
+#[test]
+fn scale_modes_keep_the_aspect_ratio()
+{
+    let viewport = |mode: ScaleMode, width, height| mode.get_viewport(width, height, 320, 320);
+
+    assert_eq!(viewport(ScaleMode::Letterbox, 800, 600), Viewport { x: 100, y: 0, width: 600, height: 600 });
+    assert_eq!(viewport(ScaleMode::IntegerScale, 800, 600), Viewport { x: 240, y: 140, width: 320, height: 320 });
+    assert_eq!(viewport(ScaleMode::IntegerScale, 1400, 1000), Viewport { x: 220, y: 20, width: 960, height: 960 });
+    assert_eq!(viewport(ScaleMode::Fill, 800, 600), Viewport { x: 0, y: -100, width: 800, height: 800 });
+    //Below the base resolution there is no whole multiple
+    assert_eq!(viewport(ScaleMode::IntegerScale, 200, 100), Viewport { x: 50, y: 0, width: 100, height: 100 });
+}
+
+#[test]
+fn resizing_recreates_the_render_targets_and_moves_the_viewport()
+{
+    let (mut view, log) = create_view();
+    view.update_map(map(20, 10)).unwrap();
+    log.borrow_mut().clear();
+
+    view.resize(800, 600).unwrap();
+    view.draw();
+
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateRenderTarget { width: 800, height: 400, .. })));
+    assert!(log.borrow().contains(&RenderCommand::SetViewport { x: 0, y: 100, width: 800, height: 400 }));
+    assert!(view.resize(0, 600).is_err());
+}
+
+#[test]
+fn render_resolution_keeps_the_map_aspect_and_scales_up_in_whole_multiples()
+{
+    let (mut view, log) = create_view();
+    view.update_map(map(20, 10)).unwrap();
+    view.resize(1280, 720).unwrap();
+    view.set_scale_mode(ScaleMode::IntegerScale).unwrap();
+    log.borrow_mut().clear();
+
+    view.set_render_resolution(Some((320, 180))).unwrap();
+    view.draw();
+
+    assert_eq!(view.get_scene_size(), (320, 160));
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateRenderTarget { width: 320, height: 160, .. })));
+    assert!(log.borrow().contains(&RenderCommand::SetViewport { x: 0, y: 0, width: 320, height: 160 }));
+    assert_eq!(view.get_viewport(), Viewport { x: 0, y: 40, width: 1280, height: 640 });
+    assert!(view.set_render_resolution(Some((0, 180))).is_err());
+
+    view.set_render_resolution(None).unwrap();
+    assert_eq!(view.get_scene_size(), (1280, 640));
+}
+
+#[test]
+fn tile_maps_are_divided_into_the_grid_of_their_atlas()
+{
+    let (mut view, log) = create_view();
+    log.borrow_mut().clear();
+
+    view.set_tile_map(image::RgbaImage::new(48, 48), SpriteAtlas::parse("size 48 48\ngrid 3 3").unwrap()).unwrap();
+
+    let uniforms: Vec<(String, UniformValue)> = log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::SetUniform { name, value, .. } => Some((name.clone(), value.clone())),
+        _ => None,
+    }).collect();
+    assert_eq!(uniforms, vec![ ("tileMapWidth".to_string(), UniformValue::Float(3.0)), ("tileMapHeight".to_string(), UniformValue::Float(3.0)) ]);
+
+    assert!(view.set_tile_map(image::RgbaImage::new(64, 64), SpriteAtlas::parse("size 64 64\ngrid 3 3").unwrap()).is_err());
+    assert!(view.set_tile_map(image::RgbaImage::new(64, 64), SpriteAtlas::parse("size 64 64").unwrap()).is_err());
+}
+
+#[test]
+fn texture_filter_applies_to_tile_maps_and_the_scene()
+{
+    let (mut view, log) = create_view();
+    log.borrow_mut().clear();
+
+    view.set_texture_filter(TextureFilter::Nearest);
+    view.set_render_resolution(Some((64, 64))).unwrap();
+    view.set_tile_map(image::RgbaImage::new(64, 64), SpriteAtlas::parse("size 64 64\ngrid 2 2").unwrap()).unwrap();
+
+    let filters = log.borrow().iter().filter(|command| matches!(command, RenderCommand::SetTextureFilter { linear: false, .. })).count();
+    //Tile map, sprite tile map, scene and the two targets of the post process effects
+    assert_eq!(filters, 5);
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateRenderTarget { width: 64, height: 64, linear: false, .. })));
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateTexture { linear: false, .. })));
+}
+
+fn effects(names: &[PostProcessEffects]) -> PostProcessViewModel
+{
+    PostProcessViewModel { effects: names.iter().map(|name| PostProcessEffect { name: *name, running_time: 0.0, max_running_time: 1.0, parameters: PostProcessParameters::default_for(*name) }).collect() }
+}
+
+fn render_target_textures(log: &RenderLog) -> Vec<(u32, u32)>
+{
+    log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::CreateRenderTarget { framebuffer, texture, .. } => Some((*framebuffer, *texture)),
+        _ => None,
+    }).collect()
+}
+
+//The framebuffers drawn into and the textures read, in the order of the frame
+fn passes(log: &RenderLog) -> Vec<RenderCommand>
+{
+    log.borrow().iter().filter(|command| matches!(command, RenderCommand::BindRenderTarget { .. } | RenderCommand::BindTexture { .. } | RenderCommand::SetUniform { .. })).cloned().collect()
+}
+
+#[test]
+fn effects_read_the_result_of_the_effect_before()
+{
+    let (mut view, log) = create_view();
+    //Scene, then the two ping-pong targets
+    let targets = render_target_textures(&log);
+    view.update(foxes(0), no_particles(), effects(&[ PostProcessEffects::VIGNETTE ]), CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.0).unwrap();
+    view.flash_screen(1.0);
+    view.update(foxes(0), no_particles(), effects(&[ PostProcessEffects::VIGNETTE ]), CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.0).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    let passes: Vec<RenderCommand> = passes(&log).into_iter().filter(|command| !matches!(command, RenderCommand::SetUniform { .. })).collect();
+    let scene = targets[0];
+    let first = targets[1];
+    let second = targets[2];
+    assert_eq!(passes[passes.len() - 6..].to_vec(), vec![
+        RenderCommand::BindRenderTarget { framebuffer: Some(first.0) },
+        RenderCommand::BindTexture { texture: scene.1 },
+        RenderCommand::BindRenderTarget { framebuffer: Some(second.0) },
+        RenderCommand::BindTexture { texture: first.1 },
+        RenderCommand::BindRenderTarget { framebuffer: None },
+        RenderCommand::BindTexture { texture: second.1 },
+    ]);
+}
+
+#[test]
+fn effects_run_in_the_given_order_and_can_be_disabled()
+{
+    let (mut view, log) = create_view();
+    let targets = render_target_textures(&log);
+    view.set_post_process_effect_order(vec![ PostProcessEffects::FLASH, PostProcessEffects::VIGNETTE ]);
+    view.flash_screen(1.0);
+    view.update(foxes(0), no_particles(), effects(&[ PostProcessEffects::VIGNETTE ]), CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.0).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    //The flash is red by default, so it is the effect drawn into the first target
+    let passes = passes(&log);
+    let first_pass = passes.iter().position(|command| *command == RenderCommand::BindRenderTarget { framebuffer: Some(targets[1].0) }).unwrap();
+    assert!(matches!(&passes[first_pass + 2], RenderCommand::SetUniform { name, value: UniformValue::Vec3(color), .. } if name == "color" && color.x == 1.0));
+
+    view.set_post_process_effect_enabled(PostProcessEffects::FLASH, false);
+    view.set_post_process_effect_enabled(PostProcessEffects::VIGNETTE, false);
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    //Without effects the scene is scaled up directly
+    assert!(!log.borrow().iter().any(|command| matches!(command, RenderCommand::SetUniform { .. })));
+    assert!(log.borrow().ends_with(&[ RenderCommand::BindTexture { texture: targets[0].1 }, RenderCommand::DrawTriangles { first_vertex: 0, vertex_count: 6 } ]));
+}
+
+#[test]
+fn effect_parameters_are_eased_over_the_lifetime()
+{
+    let (mut view, log) = create_view();
+    let red_vignette = PostProcessParameters {
+        color: Eased::constant(cgmath::Vector3 { x: 1.0, y: 0.0, z: 0.0 }),
+        radius: Eased::new(0.6, 0.2, Easing::EaseOut),
+        strength: Eased::new(0.0, 1.0, Easing::EaseIn),
+    };
+    let effect = PostProcessEffect { name: PostProcessEffects::VIGNETTE, running_time: 0.15, max_running_time: 0.3, parameters: red_vignette };
+    view.update(foxes(0), no_particles(), PostProcessViewModel { effects: vec![ effect ] }, CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.0).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    let uniforms: Vec<(String, UniformValue)> = log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::SetUniform { name, value, .. } => Some((name.clone(), value.clone())),
+        _ => None,
+    }).collect();
+    //Half way, ease out has gone 3/4 and ease in 1/4 of the way
+    assert_eq!(uniforms, vec![
+        ("color".to_string(), UniformValue::Vec3(cgmath::Vector3 { x: 1.0, y: 0.0, z: 0.0 })),
+        ("radius".to_string(), UniformValue::Float(0.3)),
+        ("strength".to_string(), UniformValue::Float(0.25)),
+    ]);
+}
+
+#[test]
+fn easings_start_at_0_and_end_at_1()
+{
+    for easing in [ Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut ].iter()
+    {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+        assert_eq!(easing.apply(2.0), 1.0);
+    }
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+}
+
+#[test]
+fn effects_of_the_model_keep_their_type()
+{
+    let (mut view, log) = create_view();
+    //The post process shaders are created last, in the order of PostProcessEffects::ALL
+    let programs: Vec<u32> = log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::CreateProgram { program } => Some(*program),
+        _ => None,
+    }).collect();
+    let effect_programs = &programs[programs.len() - PostProcessEffects::ALL.len()..];
+    view.update(foxes(0), no_particles(), effects(&[ PostProcessEffects::DESATURATE ]), CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }, 0.0).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    let desaturate = effect_programs[PostProcessEffects::ALL.iter().position(|x| *x == PostProcessEffects::DESATURATE).unwrap()];
+    let vignette = effect_programs[PostProcessEffects::ALL.iter().position(|x| *x == PostProcessEffects::VIGNETTE).unwrap()];
+    assert!(log.borrow().contains(&RenderCommand::UseProgram { program: desaturate }));
+    assert!(!log.borrow().contains(&RenderCommand::UseProgram { program: vignette }));
+}
+
+fn still_camera() -> CameraViewModel
+{
+    CameraViewModel { position: cgmath::Vector2 { x: 0.0, y: 0.0 }, zoom: 1.0, rotation: 0.0 }
+}
+
+fn iris(duration: f32) -> Transition
+{
+    Transition::new(TransitionKind::Iris, duration, cgmath::Vector2 { x: 0.5, y: 0.5 }, cgmath::Vector2 { x: 0.2, y: 0.8 })
+}
+
+#[test]
+fn snapshots_copy_the_last_frame()
+{
+    let (mut view, log) = create_view();
+    let scene = render_target_textures(&log)[0];
+    view.draw();
+    log.borrow_mut().clear();
+
+    view.snapshot_scene().unwrap();
+
+    let snapshot = render_target_textures(&log)[0];
+    assert_eq!(passes(&log), vec![
+        RenderCommand::BindRenderTarget { framebuffer: Some(snapshot.0) },
+        RenderCommand::BindTexture { texture: scene.1 },
+        RenderCommand::BindRenderTarget { framebuffer: None },
+    ]);
+}
+
+#[test]
+fn transitions_need_a_snapshot()
+{
+    let (mut view, _) = create_view();
+    assert!(view.start_transition(iris(1.0)).is_err());
+    assert!(!view.is_transition_running());
+}
+
+#[test]
+fn transitions_mix_the_snapshot_into_the_scene_until_they_are_finished()
+{
+    let (mut view, log) = create_view();
+    view.draw();
+    view.snapshot_scene().unwrap();
+    let snapshot = *render_target_textures(&log).last().unwrap();
+    view.start_transition(iris(1.0)).unwrap();
+    view.update(foxes(0), no_particles(), effects(&[]), still_camera(), 0.25).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    assert!(view.is_transition_running());
+    assert!(log.borrow().contains(&RenderCommand::BindTextureToUnit { texture: snapshot.1, unit: 1 }));
+
+    log.borrow_mut().clear();
+    view.update(foxes(0), no_particles(), effects(&[]), still_camera(), 1.0).unwrap();
+    view.draw();
+
+    assert!(!view.is_transition_running());
+    assert!(log.borrow().contains(&RenderCommand::DeleteRenderTarget { framebuffer: snapshot.0, texture: snapshot.1 }));
+    assert!(!log.borrow().contains(&RenderCommand::BindTextureToUnit { texture: snapshot.1, unit: 1 }));
+}
+
+#[test]
+fn transition_progress_is_uploaded()
+{
+    let (mut view, log) = create_view();
+    view.snapshot_scene().unwrap();
+    view.start_transition(iris(2.0)).unwrap();
+    log.borrow_mut().clear();
+
+    view.update(foxes(0), no_particles(), effects(&[]), still_camera(), 0.5).unwrap();
+
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::SetUniform { name, value: UniformValue::Float(progress), .. } if name == "progress" && *progress == 0.25)));
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::SetUniform { name, value: UniformValue::Int(1), .. } if name == "kind")));
+}
+
+fn torches(count: usize) -> LightsViewModel
+{
+    LightsViewModel {
+        ambient: cgmath::Vector3 { x: 0.1, y: 0.1, z: 0.2 },
+        positions: vec![ cgmath::Vector2 { x: 0.0, y: 0.0 }; count ],
+        radii: vec![ 3.0; count ],
+        colors: vec![ cgmath::Vector3 { x: 1.0, y: 0.6, z: 0.25 }; count ],
+        intensities: vec![ 0.5; count ],
+        occluding_tiles: vec![ 0 ],
+    }
+}
+
+#[test]
+fn lights_are_multiplied_over_the_scene_before_the_effects()
+{
+    let (mut view, log) = create_view();
+    view.update_lights(Some(torches(2))).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    let commands = log.borrow();
+    let particles = commands.iter().rposition(|command| matches!(command, RenderCommand::DrawTrianglesInstanced { .. })).unwrap();
+    let multiply = commands.iter().position(|command| *command == RenderCommand::EnableMultiplyBlending).unwrap();
+    assert!(particles < multiply);
+    assert_eq!(commands[multiply + 1..].iter().filter(|command| matches!(command, RenderCommand::DrawTriangles { .. })).count(), 2);
+    assert!(commands[multiply..].contains(&RenderCommand::EnableAlphaBlending));
+}
+
+#[test]
+fn lights_are_uploaded_as_data_texture_with_their_intensity()
+{
+    let (mut view, log) = create_view();
+    log.borrow_mut().clear();
+
+    view.update_lights(Some(torches(2))).unwrap();
+
+    //Position, radius and color times intensity of each light as float bits
+    let light: Vec<i32> = [ 0.0f32, 0.0, 3.0, 0.5, 0.3, 0.125 ].iter().map(|x| x.to_bits() as i32).collect();
+    let rows = light.repeat(2);
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::CreateDataTexture { width: 6, height: 2, data, .. } if *data == rows)));
+    assert!(log.borrow().iter().any(|command| matches!(command, RenderCommand::SetUniform { name, value: UniformValue::Int(2), .. } if name == "lightCount")));
+}
+
+#[test]
+fn levels_without_lights_skip_the_lighting_pass()
+{
+    let (mut view, log) = create_view();
+    view.update_lights(Some(torches(1))).unwrap();
+    view.update_lights(None).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    assert!(!log.borrow().contains(&RenderCommand::EnableMultiplyBlending));
+}
+
+#[test]
+fn any_number_of_lights_can_be_drawn()
+{
+    let (mut view, log) = create_view();
+    view.update_lights(Some(torches(100))).unwrap();
+    view.update_lights(Some(torches(100))).unwrap();
+    log.borrow_mut().clear();
+
+    view.draw();
+
+    //The texture of the first update is reused by the second one
+    let textures: Vec<u32> = log.borrow().iter().filter_map(|command| match command
+    {
+        RenderCommand::BindTextureToUnit { texture, unit: 2 } => Some(*texture),
+        _ => None,
+    }).collect();
+    assert_eq!(textures.len(), 1);
+    assert!(log.borrow().contains(&RenderCommand::EnableMultiplyBlending));
+}